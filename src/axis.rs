@@ -1,24 +1,77 @@
 use graph::{Tools, Coord, HTML};
-use entry::Entry;
-use scale::Scale;
+use scale::{Scale, TICK_SPACING};
+use theme::Theme;
+use text::TextMetrics;
 
 pub struct Axes2d {
     pub x: Axis,
     pub y: Axis,
+    /// Value axis on the right with its own scale
+    pub y2: Option<Axis>,
+    /// Space right of the body taken by the `y2` labels and title
+    right_width: f32,
+    /// Space below the body taken by x labels and the title
+    label_height: f32,
+    /// Fitting of the x category labels
+    x_layout: Option<LabelLayout>,
+    theme: Theme,
 }
 
 impl Axes2d {
-    pub fn new((width, height): (f32, f32), x_opt: AxisOption,
-               y_opt: AxisOption) -> Axes2d {
+    pub fn new(size: (f32, f32), x_opt: AxisOption, y_opt: AxisOption,
+               theme: &Theme) -> Axes2d {
 
-        let label_height = 30.0;
-        // TODO: calculate dynamically based on max value length
-        let value_width = 30.0;
-        let color = "rgb(13,16,17)".to_string();
+        Axes2d::with_y2(size, x_opt, y_opt, None, theme)
+    }
+
+    /// Axes with a second value axis on the right if `y2_opt` is set
+    pub fn with_y2((width, height): (f32, f32), x_opt: AxisOption, y_opt: AxisOption,
+                   y2_opt: Option<AxisOption>, theme: &Theme) -> Axes2d {
+
+        let font_size = theme.font_size;
+        let x_title_height = if x_opt.title.is_some() { font_size + 8.0 } else { 0.0 };
+        let total_height = height;
+        let height = total_height - LabelLayout::horizontal_height(font_size) - x_title_height;
+
+        // The y ticks are measured with horizontal x labels, which
+        // gives the most ticks, before the x labels are fitted
+        let value_width = Axes2d::labels_width(&y_opt, height, font_size);
+        let right_width = match y2_opt {
+            Some(ref opt) => Axes2d::labels_width(opt, height, font_size),
+            None          => 0.0,
+        };
+        let color = theme.axis_color.clone();
         let x = value_width;
         let y = 0.0;
-        let width = width - x;
-        let height = height - label_height;
+        let width = width - x - right_width;
+        let x_scale = match x_opt.kind {
+            AxisKind::Category(_) => x_opt.scale.with_range(0.0, width),
+            AxisKind::Value       => Axes2d::spaced_scale(&x_opt, width, font_size),
+        };
+
+        let x_layout = match x_opt.kind {
+            AxisKind::Category(ref labels) => {
+                Some(LabelLayout::fit(labels, x_scale.segment(), font_size))
+            },
+            AxisKind::Value => None,
+        };
+        let label_height = match x_layout {
+            Some(ref layout) => layout.height,
+            None             => LabelLayout::horizontal_height(font_size),
+        } + x_title_height;
+        let height = total_height - label_height;
+        let y_scale = y_opt.scale.with_range(0.0, height);
+        let y2_axis = y2_opt.map(|opt| Axis {
+            x: x,
+            y: y,
+            width: width,
+            height: height,
+            color: color.clone(),
+            scale: opt.scale.with_range(0.0, height),
+            kind: opt.kind,
+            title: opt.title,
+            label_position: opt.label_position,
+        });
 
         let y_axis = Axis {
             x: x,
@@ -26,8 +79,10 @@ impl Axes2d {
             width: width,
             height: height,
             color: color.clone(),
+            kind: y_opt.kind,
+            title: y_opt.title,
             label_position: y_opt.label_position,
-            scale: y_opt.scale.with_range(0.0, height),
+            scale: y_scale,
         };
 
         let x_axis = Axis {
@@ -36,14 +91,65 @@ impl Axes2d {
             width: width,
             height: height,
             color: color.clone(),
+            kind: x_opt.kind,
+            title: x_opt.title,
             label_position: x_opt.label_position,
-            scale: x_opt.scale.with_range(0.0, width),
+            scale: x_scale,
         };
 
         Axes2d {
             x: x_axis,
             y: y_axis,
+            y2: y2_axis,
+            right_width: right_width,
+            label_height: label_height,
+            x_layout: x_layout,
+            theme: theme.clone(),
+        }
+    }
+
+    /// Space taken next to the body by the labels and the title of
+    /// a vertical axis
+    fn labels_width(opt: &AxisOption, height: f32, font_size: f32) -> f32 {
+        let title_width = if opt.title.is_some() { font_size + 8.0 } else { 0.0 };
+        let labels_width = match opt.kind {
+            AxisKind::Category(ref labels) => TextMetrics::max_width(labels.iter(), font_size),
+            AxisKind::Value => {
+                let scale = opt.scale.with_range(0.0, height);
+                let ticks = scale.ticks()
+                    .iter()
+                    .map(|v| scale.tick_label(*v))
+                    .collect::<Vec<String>>();
+                TextMetrics::max_width(ticks.iter(), font_size)
+            },
+        };
+
+        title_width + 10.0 + labels_width
+    }
+
+    /// Scale of a value x-axis with ticks far enough apart that their
+    /// labels don't overlap
+    fn spaced_scale(opt: &AxisOption, width: f32, font_size: f32) -> Box<Scale> {
+        let mut scale = opt.scale.with_range(0.0, width);
+        let mut spacing = TICK_SPACING;
+
+        // Fewer ticks may get longer labels (0.25 instead of 0.2), so
+        // the labels are measured again after every change
+        for _ in 0..3 {
+            let labels = scale.ticks()
+                .iter()
+                .map(|v| scale.tick_label(*v))
+                .collect::<Vec<String>>();
+            let needed = TextMetrics::max_width(labels.iter(), font_size) + 2.0 * LABEL_GAP;
+            if needed <= spacing {
+                break;
+            }
+
+            spacing = needed;
+            scale = scale.with_tick_spacing(spacing);
         }
+
+        scale
     }
 
     pub fn body(&self) -> Coord {
@@ -55,36 +161,126 @@ impl Axes2d {
         }
     }
 
-    fn labels(&self, entries: &Vec<Entry>) -> Vec<Label> {
-        entries
+    fn x_labels(&self) -> Vec<Label> {
+        match self.x_layout {
+            Some(ref layout) => self.category_labels(layout),
+            None             => self.tick_labels(),
+        }
+    }
+
+    fn y_labels(&self) -> Vec<Label> {
+        match self.y.kind {
+            AxisKind::Category(ref labels) => self.y_category_labels(labels),
+            AxisKind::Value                => self.values(),
+        }
+    }
+
+    /// Skipped labels keep their grid line
+    fn category_labels(&self, layout: &LabelLayout) -> Vec<Label> {
+        layout.lines
             .iter()
             .enumerate()
-            .map(|(i, e)| {
+            .map(|(i, lines)| {
                 let w = self.x.scale.segment();
                 let x = self.x.scale.offset(i as f32);
                 let label_x = match self.x.label_position {
                     LabelPosition::InBetween => w / 2.0,
                     LabelPosition::Normal    => 0.0,
                 };
+                let color = if i == 0 {
+                    self.y.color.clone()
+                } else {
+                    self.theme.grid_color.clone()
+                };
+
+                self.x_label(x, label_x, lines.clone(), color)
+            })
+            .collect()
+    }
+
+    /// Labels for a value x-axis. The y-axis line is added when
+    /// there is no tick at the left edge.
+    fn tick_labels(&self) -> Vec<Label> {
+        let ticks = self.x.scale.ticks()
+            .iter()
+            .map(|v| (self.x.scale.offset(*v), self.x.scale.tick_label(*v),
+                      *v == self.x.scale.baseline()))
+            .filter(|&(x, _, _)| x >= 0.0 && x <= self.x.width)
+            .map(|(x, text, is_baseline)| {
+                let color = if is_baseline || x == 0.0 {
+                    self.y.color.clone()
+                } else {
+                    self.theme.grid_color.clone()
+                };
+
+                self.x_label(x, 0.0, vec![text], color)
+            })
+            .collect::<Vec<Label>>();
+
+        if ticks.iter().any(|l| l.x == 0.0) {
+            ticks
+        } else {
+            let mut labels = vec![
+                self.x_label(0.0, 0.0, Vec::new(), self.y.color.clone())
+            ];
+            labels.extend(ticks);
+            labels
+        }
+    }
+
+    /// Rotated labels hang from their end just below the axis
+    fn x_label(&self, x: f32, label_x: f32, lines: Vec<String>, color: String) -> Label {
+        Label {
+            x: x,
+            y: self.x.height,
+            label: Text {
+                x: label_x,
+                y: if self.label_angle() == 0.0 { self.theme.font_size + 5.0 } else { 8.0 },
+                color: self.theme.text_color.clone(),
+                lines: lines,
+            },
+            line: Line {
+                x1: 0.0,
+                x2: 0.0,
+                y1: 0.0,
+                y2: -self.y.height,
+                color: color,
+            },
+        }
+    }
+
+    /// Categories from top to bottom, the line under the last one
+    /// is the x-axis
+    fn y_category_labels(&self, labels: &Vec<String>) -> Vec<Label> {
+        labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| {
+                let h = self.y.scale.segment();
+                let y = self.y.scale.offset((i + 1) as f32);
+                let label_y = match self.y.label_position {
+                    LabelPosition::InBetween => -h / 2.0,
+                    LabelPosition::Normal    => -h,
+                };
 
                 Label {
-                    x: x,
-                    y: self.x.height,
+                    x: 0.0,
+                    y: y,
                     label: Text {
-                        x: label_x,
-                        y: 17.0,
-                        color: "rgb(12,12,12)".to_string(),
-                        text: e.label.clone(),
+                        x: -5.0,
+                        y: label_y + 4.0,
+                        color: self.theme.text_color.clone(),
+                        lines: vec![label.clone()],
                     },
                     line: Line {
                         x1: 0.0,
-                        x2: 0.0,
+                        x2: self.y.width,
                         y1: 0.0,
-                        y2: -self.y.height,
-                        color: if i == 0 {
-                            self.y.color.clone()
+                        y2: 0.0,
+                        color: if i + 1 == labels.len() {
+                            self.x.color.clone()
                         } else {
-                            "rgb(203,206,206)".to_string()
+                            self.theme.grid_color.clone()
                         },
                     },
                 }
@@ -96,7 +292,7 @@ impl Axes2d {
         self.y.scale.ticks()
             .iter()
             .map(|v| {
-                let y = self.y.height - self.y.scale.offset(*v as f32);
+                let y = self.y.height - self.y.scale.offset(*v);
                 let y = if y < 0.0 { 0.0 } else { y };
 
                 Label {
@@ -105,18 +301,20 @@ impl Axes2d {
                     label: Text {
                         x: -5.0,
                         y: 4.0,
-                        color: "rgb(12,12,12)".to_string(),
-                        text: format!("{}", v),
+                        color: self.theme.text_color.clone(),
+                        lines: vec![self.y.scale.tick_label(*v)],
                     },
                     line: Line {
                         x1: 0.0,
                         x2: self.y.width,
                         y1: 0.0,
                         y2: 0.0,
-                        color: if *v == 0 {
+                        // Bottom edge stands in for the x-axis when
+                        // the range doesn't include the baseline
+                        color: if *v == self.y.scale.baseline() || y == self.y.height {
                             self.x.color.clone()
                         } else {
-                            "rgb(203,206,206)".to_string()
+                            self.theme.grid_color.clone()
                         },
                     },
                 }
@@ -124,18 +322,55 @@ impl Axes2d {
             .collect()
     }
 
-    fn vertical_lines(&self, entries: &Vec<Entry>) -> HTML {
-        let labels = self.labels(entries);
+    fn minor_values(&self) -> Vec<Line> {
+        self.y.scale.minor_ticks()
+            .iter()
+            .map(|v| {
+                let y = self.y.height - self.y.scale.offset(*v);
+
+                Line {
+                    x1: 0.0,
+                    x2: self.y.width,
+                    y1: y,
+                    y2: y,
+                    color: self.theme.minor_grid_color.clone(),
+                }
+            })
+            .collect()
+    }
+
+    fn x_minor_values(&self) -> Vec<Line> {
+        self.x.scale.minor_ticks()
+            .iter()
+            .map(|v| {
+                let x = self.x.scale.offset(*v);
+
+                Line {
+                    x1: x,
+                    x2: x,
+                    y1: 0.0,
+                    y2: self.y.height,
+                    color: self.theme.minor_grid_color.clone(),
+                }
+            })
+            .collect()
+    }
+
+    fn vertical_lines(&self) -> HTML {
+        let labels = self.x_labels();
+        let minor = self.x_minor_values();
 
         html! {
+            @for Line { x1, x2, y1, y2, color } in minor {
+                line.x-minor-line x1=(x1) x2=(x2) y1=(y1) y2=(y2) stroke=(color) stroke-width=(self.theme.grid_width) {}
+            }
+
             @for Label { x, y, label, line } in labels {
                 g.x-line transform=(Tools::tr(x, y)) {
-                    @let Text { x, y, text, color } = label {
-                        text.label x=(x) y=(y) text-anchor="middle" fill=(color) (text)
-                    }
+                    (self.x_text(label))
 
                     @let Line { x1, x2, y1, y2, color } = line {
-                        line x1=(x1) x2=(x2) y1=(y1) y2=(y2) stroke=(color) {}
+                        line x1=(x1) x2=(x2) y1=(y1) y2=(y2) stroke=(color) stroke-width=(self.theme.grid_width) {}
                     }
                 }
             }
@@ -143,28 +378,115 @@ impl Axes2d {
     }
 
     fn horizontal_lines(&self) -> HTML {
-        let values = self.values();
+        let values = self.y_labels();
+        let minor = self.minor_values();
 
         html! {
+            @for Line { x1, x2, y1, y2, color } in minor {
+                line.y-minor-line x1=(x1) x2=(x2) y1=(y1) y2=(y2) stroke=(color) stroke-width=(self.theme.grid_width) {}
+            }
+
             @for Label { x, y, label, line } in values {
                 g.y-line transform=(Tools::tr(x, y)) {
-                    @let Text { x, y, text, color } = label {
-                        text.label x=(x) y=(y) text-anchor="end" fill=(color) (text)
+                    @let Text { x, y, lines, color } = label {
+                        text.label x=(x) y=(y) text-anchor="end" fill=(color) (lines.join(" "))
                     }
 
                     @let Line { x1, x2, y1, y2, color } = line {
-                        line x1=(x1) x2=(x2) y1=(y1) y2=(y2) stroke=(color) {}
+                        line x1=(x1) x2=(x2) y1=(y1) y2=(y2) stroke=(color) stroke-width=(self.theme.grid_width) {}
+                    }
+                }
+            }
+        }
+    }
+
+    fn label_angle(&self) -> f32 {
+        self.x_layout.as_ref().map_or(0.0, |l| l.angle)
+    }
+
+    /// Wrapped lines are `tspan`s below each other
+    fn x_text(&self, Text { x, y, lines, color }: Text) -> HTML {
+        let angle = self.label_angle();
+        let line_height = self.theme.font_size * LINE_HEIGHT;
+
+        if angle != 0.0 {
+            let rotate = format!("rotate({},{},{})", -angle, x, y);
+
+            html! {
+                text.label transform=(rotate) x=(x) y=(y) dy="0.35em" text-anchor="end" fill=(color) (lines.join(" "))
+            }
+        } else if lines.len() > 1 {
+            html! {
+                text.label x=(x) y=(y) text-anchor="middle" fill=(color) {
+                    @for (i, line) in lines.iter().enumerate() {
+                        tspan x=(x) dy=(if i == 0 { 0.0 } else { line_height }) (line)
+                    }
+                }
+            }
+        } else {
+            html! {
+                text.label x=(x) y=(y) text-anchor="middle" fill=(color) (lines.join(" "))
+            }
+        }
+    }
+
+    fn titles(&self) -> HTML {
+        let x_title = (self.x.width / 2.0, self.y.height + self.label_height - 5.0);
+        // Rotated around its own origin, so `y` moves it right
+        let y_title = Tools::tr(-self.x.x, self.y.height / 2.0);
+
+        html! {
+            @if let Some(ref title) = self.x.title {
+                text.axis-title x=(x_title.0) y=(x_title.1) text-anchor="middle" fill=(self.theme.text_color) (title)
+            }
+
+            @if let Some(ref title) = self.y.title {
+                text.axis-title transform=(format!("{} rotate(-90)", y_title)) y="14" text-anchor="middle" fill=(self.theme.text_color) (title)
+            }
+        }
+    }
+
+    /// Ticks of the right axis as `(y, text)`
+    fn y2_ticks(axis: &Axis) -> Vec<(f32, String)> {
+        axis.scale.ticks()
+            .iter()
+            .map(|v| {
+                let y = axis.height - axis.scale.offset(*v);
+                (if y < 0.0 { 0.0 } else { y }, axis.scale.tick_label(*v))
+            })
+            .collect()
+    }
+
+    /// The right axis has short tick marks instead of grid lines,
+    /// so that they don't mix with the ones of the left axis
+    fn right_axis(&self) -> HTML {
+        html! {
+            @if let Some(ref axis) = self.y2 {
+                g.y2-axis transform=(Tools::tr(axis.width, 0.0)) {
+                    line x1="0" x2="0" y1="0" y2=(axis.height) stroke=(axis.color) stroke-width=(self.theme.grid_width) {}
+
+                    @for (y, text) in Axes2d::y2_ticks(axis) {
+                        g.y2-line transform=(Tools::tr(0.0, y)) {
+                            line x1="0" x2="4" y1="0" y2="0" stroke=(axis.color) stroke-width=(self.theme.grid_width) {}
+                            text.label x="7" y="4" text-anchor="start" fill=(self.theme.text_color) (text)
+                        }
+                    }
+
+                    @if let Some(ref title) = axis.title {
+                        text.axis-title transform=(format!("{} rotate(90)", Tools::tr(self.right_width, axis.height / 2.0))) y="14" text-anchor="middle" fill=(self.theme.text_color) (title)
                     }
                 }
             }
         }
     }
 
-    pub fn render(&self, entries: &Vec<Entry>) -> HTML {
+    pub fn render(&self) -> HTML {
         html! {
             g.axes transform=(Tools::tr(self.x.x, self.x.y)) {
                 (self.horizontal_lines())
-                (self.vertical_lines(entries))
+                (self.vertical_lines())
+                (self.right_axis())
+                (self.titles())
             }
         }
     }
@@ -177,15 +499,26 @@ pub struct Axis {
     pub y: f32,
     pub width: f32,
     pub height: f32,
+    pub kind: AxisKind,
+    pub title: Option<String>,
     pub label_position: LabelPosition,
     pub scale: Box<Scale>,
 }
 
 pub struct AxisOption {
     pub scale: Box<Scale>,
+    pub kind: AxisKind,
+    pub title: Option<String>,
     pub label_position: LabelPosition,
 }
 
+pub enum AxisKind {
+    /// One label per category, placed at the category index
+    Category(Vec<String>),
+    /// Labels are taken from the scale ticks
+    Value,
+}
+
 struct Label {
     x: f32,
     y: f32,
@@ -196,7 +529,7 @@ struct Label {
 struct Text {
     x: f32,
     y: f32,
-    text: String,
+    lines: Vec<String>,
     color: String,
 }
 
@@ -208,6 +541,102 @@ struct Line {
     color: String,
 }
 
+/// Line spacing of wrapped labels in em
+const LINE_HEIGHT: f32 = 1.2;
+/// Wrapped labels with more lines are rotated instead
+const MAX_LINES: usize = 2;
+/// Rotated labels are truncated to this width
+const MAX_ROTATED_WIDTH: f32 = 100.0;
+/// Minimum space between two labels
+const LABEL_GAP: f32 = 4.0;
+
+/// Category labels of the x-axis fitted into their slots. Labels that
+/// are too wide are wrapped, then rotated by 45 or 90 degrees and
+/// truncated, and finally skipped if even vertical labels overlap.
+struct LabelLayout {
+    /// Counterclockwise rotation in degrees
+    angle: f32,
+    /// Lines of every label, none for the skipped ones
+    lines: Vec<Vec<String>>,
+    /// Space taken below the axis
+    height: f32,
+}
+
+impl LabelLayout {
+    fn fit(labels: &[String], slot: f32, font_size: f32) -> LabelLayout {
+        let line_height = font_size * LINE_HEIGHT;
+        let max_width = slot - LABEL_GAP;
+
+        if TextMetrics::max_width(labels.iter(), font_size) <= max_width {
+            return LabelLayout {
+                angle: 0.0,
+                lines: labels.iter().map(|l| vec![l.clone()]).collect(),
+                height: LabelLayout::horizontal_height(font_size),
+            };
+        }
+
+        let wrapped = labels
+            .iter()
+            .map(|l| TextMetrics::wrap(l, max_width, font_size))
+            .collect::<Vec<Vec<String>>>();
+        let fits = wrapped.iter().all(|lines| {
+            lines.len() <= MAX_LINES &&
+            TextMetrics::max_width(lines.iter(), font_size) <= max_width
+        });
+
+        if fits {
+            let count = wrapped.iter().fold(1, |acc, lines| acc.max(lines.len()));
+
+            return LabelLayout {
+                angle: 0.0,
+                lines: wrapped,
+                height: LabelLayout::horizontal_height(font_size) + (count - 1) as f32 * line_height,
+            };
+        }
+
+        // Horizontal space taken by a rotated line
+        let angle: f32 = if slot * 45.0f32.to_radians().sin() >= line_height { 45.0 } else { 90.0 };
+        let footprint = line_height / angle.to_radians().sin();
+        let boxes = (0..labels.len())
+            .map(|i| Coord {
+                x: i as f32 * slot - footprint / 2.0,
+                y: 0.0,
+                width: footprint,
+                height: 1.0,
+            })
+            .collect::<Vec<Coord>>();
+        let kept = TextMetrics::thin(&boxes);
+        let lines = labels
+            .iter()
+            .enumerate()
+            .map(|(i, l)| {
+                if kept.contains(&i) {
+                    vec![TextMetrics::truncate(l, MAX_ROTATED_WIDTH, font_size)]
+                } else {
+                    Vec::new()
+                }
+            })
+            .collect::<Vec<Vec<String>>>();
+        let longest = lines
+            .iter()
+            .flat_map(|l| l.iter())
+            .fold(0.0, |w: f32, l| w.max(TextMetrics::width(l, font_size)));
+
+        // Rotated labels start 8px below the axis
+        LabelLayout {
+            angle: angle,
+            lines: lines,
+            height: longest * angle.to_radians().sin() +
+                    font_size * angle.to_radians().cos() + 12.0 + 8.0,
+        }
+    }
+
+    /// Space taken by a single line of labels
+    fn horizontal_height(font_size: f32) -> f32 {
+        font_size * 1.5 + 12.0
+    }
+}
+
 //#[derive(Debug, Clone)]
 pub enum LabelPosition {
     /// Next to separator
@@ -215,3 +644,46 @@ pub enum LabelPosition {
     /// Between separators
     InBetween,
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(labels: &[&str]) -> Vec<String> {
+        labels.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn label_layout() {
+        let layout = LabelLayout::fit(&labels(&["Mon", "Tue"]), 50.0, 10.0);
+        assert_eq!(layout.angle, 0.0);
+        assert_eq!(layout.lines, vec![vec!["Mon"], vec!["Tue"]]);
+        assert_eq!(layout.height, 27.0);
+
+        let layout = LabelLayout::fit(&labels(&["New York", "Los Angeles"]), 50.0, 10.0);
+        assert_eq!(layout.angle, 0.0);
+        assert_eq!(layout.lines, vec![vec!["New York"], vec!["Los", "Angeles"]]);
+        assert_eq!(layout.height, 39.0);
+
+        let layout = LabelLayout::fit(&labels(&["September", "October"]), 20.0, 10.0);
+        assert_eq!(layout.angle, 45.0);
+        assert_eq!(layout.lines.len(), 2);
+
+        let days = (1..51).map(|d| format!("2024-01-{:02}", d)).collect::<Vec<String>>();
+        let layout = LabelLayout::fit(&days, 5.0, 10.0);
+        assert_eq!(layout.angle, 90.0);
+        assert_eq!(layout.lines[0], vec!["2024-01-01"]);
+        assert!(layout.lines[1].is_empty());
+        assert_eq!(layout.lines.iter().filter(|l| !l.is_empty()).count(), 17);
+    }
+
+    #[test]
+    fn label_layout_truncate() {
+        let long = "A very long category name that goes on and on".to_string();
+        let layout = LabelLayout::fit(&[long], 10.0, 10.0);
+        let label = &layout.lines[0][0];
+        assert!(label.ends_with("\u{2026}"));
+        assert!(TextMetrics::width(label, 10.0) <= MAX_ROTATED_WIDTH);
+    }
+}
@@ -1,12 +1,36 @@
+use std::f32::{MAX, MIN};
+
 use graph::{Graph, Tools, Coord, Padding, HTML, Size};
 use entry::Entry;
-use axis::{Axes2d, LabelPosition, AxisOption};
-use scale::{LinearScale, LinearRoundedScale};
+use series::Series;
+use title::Title;
+use theme::Theme;
+use legend::{Legend, LegendItem, Swatch};
+use axis::{Axes2d, Axis, LabelPosition, AxisOption, AxisKind};
+use scale::{Scale, LinearScale, ScaleKind, ScaleOptions};
+use interpolation::Interpolation;
+use marker::Marker;
+
+/// Marker size of the lines drawn against the right axis
+const LINE_MARKER_SIZE: f32 = 6.0;
 
 pub struct BarBuilder {
     width: f32,
     height: f32,
-    entries: Option<Vec<Entry>>,
+    theme: Theme,
+    title: Option<String>,
+    subtitle: Option<String>,
+    x_title: Option<String>,
+    y_title: Option<String>,
+    y_scale: ScaleOptions,
+    y2_title: Option<String>,
+    y2_scale: ScaleOptions,
+    mode: BarMode,
+    orientation: Orientation,
+    group_padding: f32,
+    bar_padding: f32,
+    legend: Option<Legend>,
+    series: Vec<Series>,
 }
 
 impl BarBuilder {
@@ -14,7 +38,20 @@ impl BarBuilder {
         BarBuilder {
             width: 500.0,
             height: 500.0,
-            entries: None,
+            theme: Theme::light(),
+            title: None,
+            subtitle: None,
+            x_title: None,
+            y_title: None,
+            y_scale: ScaleOptions::new(ScaleKind::Linear),
+            y2_title: None,
+            y2_scale: ScaleOptions::new(ScaleKind::Linear),
+            mode: BarMode::Grouped,
+            orientation: Orientation::Vertical,
+            group_padding: 1.0 / 3.0,
+            bar_padding: 0.1,
+            legend: None,
+            series: Vec::new(),
         }
     }
 
@@ -28,121 +65,492 @@ impl BarBuilder {
         self
     }
 
+    /// Set the data as a single unnamed series, replaces the series
+    /// added before
     pub fn entries(mut self, entries: Vec<Entry>) -> BarBuilder {
-        self.entries = Some(entries);
+        self.series = vec![Series::new("", entries)];
+        self
+    }
+
+    /// Add a series, entries are matched to categories by their index
+    pub fn series(mut self, series: Series) -> BarBuilder {
+        self.series.push(series);
+        self
+    }
+
+    /// Stack the series on top of each other, negative values
+    /// are stacked below zero
+    pub fn stacked(mut self) -> BarBuilder {
+        self.mode = BarMode::Stacked;
+        self
+    }
+
+    /// Horizontal bars put categories on the y-axis, which leaves
+    /// more room for long labels
+    pub fn orientation(mut self, orientation: Orientation) -> BarBuilder {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Fraction of the category band left empty around each group of bars
+    pub fn group_padding(mut self, padding: f32) -> BarBuilder {
+        self.group_padding = padding;
+        self
+    }
+
+    /// Gap between the bars of a group as a fraction of the bar width
+    pub fn bar_padding(mut self, padding: f32) -> BarBuilder {
+        self.bar_padding = padding;
+        self
+    }
+
+    /// Legend with a box per series, shown by default
+    /// when there is more than one series
+    pub fn legend(mut self, legend: Legend) -> BarBuilder {
+        self.legend = Some(legend);
+        self
+    }
+
+    /// Scale of the value axis, linear by default
+    pub fn y_scale<S>(mut self, y_scale: S) -> BarBuilder where S: Into<ScaleOptions> {
+        self.y_scale = y_scale.into();
+        self
+    }
+
+    /// Scale of the right value axis. Series bound to it with
+    /// `Series::y_axis(YAxis::Right)` are drawn as a line through the
+    /// category centers, e.g. a rate over revenue bars. Horizontal bars
+    /// have no right axis and draw every series as bars.
+    pub fn y2_scale<S>(mut self, y2_scale: S) -> BarBuilder where S: Into<ScaleOptions> {
+        self.y2_scale = y2_scale.into();
+        self
+    }
+
+    /// Title drawn rotated along the right value axis
+    pub fn y2_title<S>(mut self, title: S) -> BarBuilder where S: Into<String> {
+        self.y2_title = Some(title.into());
+        self
+    }
+
+    /// Colors and fonts, `Theme::light()` by default
+    pub fn theme(mut self, theme: Theme) -> BarBuilder {
+        self.theme = theme;
+        self
+    }
+
+    pub fn title<S>(mut self, title: S) -> BarBuilder where S: Into<String> {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn subtitle<S>(mut self, subtitle: S) -> BarBuilder where S: Into<String> {
+        self.subtitle = Some(subtitle.into());
+        self
+    }
+
+    pub fn x_title<S>(mut self, title: S) -> BarBuilder where S: Into<String> {
+        self.x_title = Some(title.into());
+        self
+    }
+
+    /// Title drawn rotated along the y-axis
+    pub fn y_title<S>(mut self, title: S) -> BarBuilder where S: Into<String> {
+        self.y_title = Some(title.into());
         self
     }
 
     pub fn build(self) -> Bar {
         let padding = Padding::with_same(15.0);
+        let theme = self.theme;
         let (width, height) = (self.width, self.height);
-        let content = Coord::from_padding(&padding, (0.0, 0.0, width, height));
-        let entries = match self.entries {
-            Some(e) => e,
-            None    => Vec::with_capacity(0),
+        let series = self.series;
+        let right = match self.orientation {
+            Orientation::Vertical   => series.iter().map(|s| s.y_axis.is_right()).collect(),
+            Orientation::Horizontal => vec![false; series.len()],
         };
+        let items = BarBuilder::legend_items(&series, &right, &self.mode, &theme);
+        let legend = match self.legend {
+            Some(l)                     => Some(l),
+            None if series.len() > 1    => Some(Legend::new()),
+            None                        => None,
+        }.map(|l| l.items(items));
+        let content = Coord::from_padding(&padding, (0.0, 0.0, width, height));
+        let title = Title::new(self.title, self.subtitle);
+        let (content, title_body) = title.split(&content);
+        let (content, legend) = Legend::layout(legend, content, &theme);
 
         let axes = {
-            let x_opt = AxisOption {
+            let labels = Tools::series_labels(&series);
+            let category_opt = AxisOption {
                 scale: Box::new(
-                    LinearScale::new(0.0, entries.len() as f32)
+                    LinearScale::new(0.0, labels.len() as f32)
                 ),
+                kind: AxisKind::Category(labels),
+                title: None,
                 label_position: LabelPosition::InBetween,
             };
 
-            let y_opt = {
-                let (min, max) = {
-                    let (min, max) = Tools::min_max_entry_values(&entries);
-                    ((min as f32).min(0.0), (max as f32).max(0.0))
+            let value_opt = {
+                let bars = series
+                    .iter()
+                    .zip(right.iter())
+                    .filter(|&(_, r)| !*r)
+                    .map(|(s, _)| s)
+                    .collect::<Vec<&Series>>();
+                let (min, max) = match self.mode {
+                    BarMode::Grouped => BarBuilder::min_max_values(&bars),
+                    BarMode::Stacked => BarBuilder::min_max_stacked_values(&bars),
                 };
 
                 AxisOption {
-                    scale: Box::new(
-                        LinearRoundedScale::new(min as f32, max as f32)
-                    ),
+                    scale: self.y_scale.scale(min, max),
+                    kind: AxisKind::Value,
+                    title: None,
                     label_position: LabelPosition::Normal,
                 }
             };
 
-            Axes2d::new((content.width, content.height), x_opt, y_opt)
+            let (x_opt, y_opt) = match self.orientation {
+                Orientation::Vertical   => (category_opt, value_opt),
+                Orientation::Horizontal => (value_opt, category_opt),
+            };
+
+            let y2_opt = if right.iter().any(|r| *r) {
+                let lines = series
+                    .iter()
+                    .zip(right.iter())
+                    .filter(|&(_, r)| *r)
+                    .map(|(s, _)| s)
+                    .collect::<Vec<&Series>>();
+                let (min, max) = BarBuilder::min_max_values(&lines);
+
+                Some(AxisOption {
+                    scale: self.y2_scale.scale(min, max),
+                    kind: AxisKind::Value,
+                    title: self.y2_title,
+                    label_position: LabelPosition::Normal,
+                })
+            } else {
+                None
+            };
+
+            Axes2d::with_y2((content.width, content.height),
+                            AxisOption { title: self.x_title, .. x_opt },
+                            AxisOption { title: self.y_title, .. y_opt },
+                            y2_opt,
+                            &theme)
         };
 
         let body = axes.body();
 
         Bar {
             size: Size { width: width, height: height },
-            padding: padding,
+            title: (title, title_body),
+            theme: theme,
+            content: content,
             axes: axes,
             body: body,
-            entries: entries,
+            legend: legend,
+            mode: self.mode,
+            orientation: self.orientation,
+            group_padding: self.group_padding,
+            bar_padding: self.bar_padding,
+            series: series,
+            right: right,
         }
     }
+
+    fn legend_items(series: &Vec<Series>, right: &Vec<bool>, mode: &BarMode,
+                    theme: &Theme) -> Vec<LegendItem> {
+        series
+            .iter()
+            .enumerate()
+            .map(|(j, s)| LegendItem {
+                text: s.name.clone(),
+                color: Bar::series_fill(series, mode, theme, j),
+                swatch: if right[j] { Swatch::Line(Some(Marker::Circle)) } else { Swatch::Rect },
+            })
+            .collect()
+    }
+
+    fn min_max_values(series: &Vec<&Series>) -> (f32, f32) {
+        series
+            .iter()
+            .map(|s| Tools::min_max_entry_values(&s.entries))
+            .fold((MAX, MIN), |(min, max), (s_min, s_max)| {
+                (min.min(s_min), max.max(s_max))
+            })
+    }
+
+    /// Lowest sum of negative and highest sum of positive values
+    /// over all categories
+    fn min_max_stacked_values(series: &Vec<&Series>) -> (f32, f32) {
+        let categories = series
+            .iter()
+            .fold(0, |acc, s| acc.max(s.entries.len()));
+
+        (0..categories)
+            .map(|i| {
+                series
+                    .iter()
+                    .filter_map(|s| s.entries.get(i).and_then(|e| e.value))
+                    .fold((0.0, 0.0), |(neg, pos), v| {
+                        if v < 0.0 {
+                            (neg + v, pos)
+                        } else {
+                            (neg, pos + v)
+                        }
+                    })
+            })
+            .fold((0.0, 0.0), |(min, max): (f32, f32), (neg, pos)| {
+                (min.min(neg), max.max(pos))
+            })
+    }
 }
 
 pub struct Bar {
     size: Size,
-    entries: Vec<Entry>,
-    padding: Padding,
+    title: (Title, Coord),
+    theme: Theme,
+    series: Vec<Series>,
+    mode: BarMode,
+    orientation: Orientation,
+    group_padding: f32,
+    bar_padding: f32,
+    /// Series drawn as a line against the right axis
+    right: Vec<bool>,
+    legend: Option<(Legend, Coord)>,
+    content: Coord,
     body: Coord,
     axes: Axes2d,
 }
 
 impl Bar {
     fn bars(&self) -> Vec<BarColumn> {
-        self.entries
-            .iter()
+        match self.mode {
+            BarMode::Grouped => self.grouped_bars(),
+            BarMode::Stacked => self.stacked_bars(),
+        }
+    }
+
+    fn category_axis(&self) -> &Axis {
+        match self.orientation {
+            Orientation::Vertical   => &self.axes.x,
+            Orientation::Horizontal => &self.axes.y,
+        }
+    }
+
+    fn value_axis(&self) -> &Axis {
+        match self.orientation {
+            Orientation::Vertical   => &self.axes.y,
+            Orientation::Horizontal => &self.axes.x,
+        }
+    }
+
+    /// Missing values leave an empty slot
+    fn grouped_bars(&self) -> Vec<BarColumn> {
+        let baseline = self.value_axis().scale.baseline();
+
+        self.bar_series()
+            .into_iter()
             .enumerate()
-            .map(|(i, e)| {
-                let box_w = self.axes.x.scale.segment();
-                let dx = box_w / 6.0;
-                let w = box_w - 2.0 * dx;
-                let h = self.axes.y.scale.offset(e.value as f32);
-                let zero = self.axes.y.scale.offset(0.0);
-                let bar_height = h - zero;
-                let y = if bar_height > 0.0 {
-                    self.axes.y.height - zero - bar_height
-                } else {
-                    self.axes.y.height - zero
+            .flat_map(|(k, (j, s))| {
+                s.entries
+                    .iter()
+                    .enumerate()
+                    .filter_map(move |(i, e)| {
+                        e.value.map(|v| self.column(i, self.slot(k), baseline, v, self.fill(j)))
+                    })
+            })
+            .collect()
+    }
+
+    fn stacked_bars(&self) -> Vec<BarColumn> {
+        let categories = self.series
+            .iter()
+            .fold(0, |acc, s| acc.max(s.entries.len()));
+        let mut columns = Vec::new();
+
+        for i in 0..categories {
+            let (mut neg, mut pos) = (0.0, 0.0);
+
+            for (j, s) in self.bar_series() {
+                let value = match s.entries.get(i).and_then(|e| e.value) {
+                    Some(v) => v,
+                    None    => continue,
                 };
 
-                BarColumn {
-                    x: self.axes.x.scale.offset(i as f32),
-                    y: y,
-                    dx: dx,
-                    width: w,
-                    height: bar_height.abs(),
-                    fill: "rgba(131,43,189,0.6)".to_string()
-                }
+                let from = if value < 0.0 { neg } else { pos };
+                let to = from + value;
+                if value < 0.0 { neg = to } else { pos = to }
+
+                columns.push(self.column(i, self.slot(0), from, to, self.fill(j)));
+            }
+        }
+
+        columns
+    }
+
+    /// Series drawn as bars with their index
+    fn bar_series(&self) -> Vec<(usize, &Series)> {
+        self.series
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| !self.right[j])
+            .collect()
+    }
+
+    /// Offset and width of the `j`-th bar inside a category band
+    fn slot(&self, j: usize) -> (f32, f32) {
+        let box_w = self.category_axis().scale.segment();
+        let n = match self.mode {
+            BarMode::Grouped => self.bar_series().len().max(1) as f32,
+            BarMode::Stacked => 1.0,
+        };
+        let dx = box_w * self.group_padding / 2.0;
+        let w = (box_w - 2.0 * dx) / (n + (n - 1.0) * self.bar_padding);
+
+        (dx + (j as f32) * w * (1.0 + self.bar_padding), w)
+    }
+
+    /// Column in the `i`-th category spanning values from `from` to `to`
+    fn column(&self, i: usize, (dx, w): (f32, f32), from: f32, to: f32,
+              fill: String) -> BarColumn {
+        let a = self.value_axis().scale.offset(from);
+        let b = self.value_axis().scale.offset(to);
+        let band = self.category_axis().scale.offset(i as f32);
+
+        match self.orientation {
+            Orientation::Vertical   => BarColumn {
+                x: band,
+                y: self.axes.y.height - a.max(b),
+                dx: dx,
+                dy: 0.0,
+                width: w,
+                height: (a - b).abs(),
+                fill: fill,
+            },
+            Orientation::Horizontal => BarColumn {
+                x: a.min(b),
+                y: band,
+                dx: 0.0,
+                dy: dx,
+                width: (a - b).abs(),
+                height: w,
+                fill: fill,
+            },
+        }
+    }
+
+    /// Lines of the series bound to the right axis, broken at
+    /// missing values, with a marker at every point
+    fn lines(&self) -> Vec<(String, Vec<(f32, f32)>, String)> {
+        let axis = match self.axes.y2 {
+            Some(ref axis) => axis,
+            None           => return Vec::new(),
+        };
+        let scale: &Box<Scale> = &axis.scale;
+        let band = self.axes.x.scale.segment();
+
+        self.series
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| self.right[j])
+            .map(|(j, s)| {
+                let points = s.values()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| v.map(|v| {
+                        (self.axes.x.scale.offset(i as f32) + band / 2.0,
+                         axis.height - scale.offset(v))
+                    }))
+                    .collect::<Vec<Option<(f32, f32)>>>();
+                let d = points
+                    .split(|p| p.is_none())
+                    .map(|run| {
+                        let run = run.iter().filter_map(|p| *p).collect::<Vec<_>>();
+                        Interpolation::Linear.path(&run)
+                    })
+                    .collect::<Vec<String>>()
+                    .concat();
+
+                (d, points.into_iter().filter_map(|p| p).collect(), self.fill(j))
             })
             .collect()
     }
+
+    fn fill(&self, j: usize) -> String {
+        Bar::series_fill(&self.series, &self.mode, &self.theme, j)
+    }
+
+    fn series_fill(series: &Vec<Series>, mode: &BarMode, theme: &Theme, j: usize) -> String {
+        match (&series[j].color, mode) {
+            (&Some(ref c), _) => c.clone(),
+            (&None, &BarMode::Grouped) if series.len() == 1 => {
+                theme.bar_color.clone()
+            },
+            (&None, _)        => theme.color(j),
+        }
+    }
 }
 
 impl Graph for Bar {
     fn into_html(&self) -> HTML {
         let bars = self.bars();
+        let lines = self.lines();
 
         html! {
-            svg width=(self.size.width) height=(self.size.height) xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" {
+            svg width=(self.size.width) height=(self.size.height) xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" font-family=(self.theme.font_family) font-size=(self.theme.font_size) {
+
+                (self.theme.background(&self.size))
+                (self.title.0.render(&self.title.1, &self.theme))
 
-                g.content transform=(Tools::tr(self.padding.left, self.padding.top)) {
-                    @for BarColumn { x, y, dx, width, height, fill } in bars {
+                g.content transform=(Tools::tr(self.content.x, self.content.y)) {
+                    @for BarColumn { x, y, dx, dy, width, height, fill } in bars {
                         g.bar-box transform=(Tools::tr(self.body.x + x, self.body.y + y)) {
-                            rect.bar fill=(fill) x=(dx) width=(width) height=(height) {}
+                            rect.bar fill=(fill) x=(dx) y=(dy) width=(width) height=(height) {}
+                        }
+                    }
+
+                    (self.axes.render())
+
+                    g.line-box transform=(Tools::tr(self.body.x, self.body.y)) {
+                        @for (d, points, stroke) in lines {
+                            path.line fill="none" stroke-width=(self.theme.line_width) stroke=(stroke) d=(d) {}
+
+                            @for (x, y) in points {
+                                (Marker::Circle.render(x, y, LINE_MARKER_SIZE, &stroke, None))
+                            }
                         }
                     }
+                }
 
-                    (self.axes.render(&self.entries))
+                @if let Some((ref legend, ref legend_body)) = self.legend {
+                    (legend.render(legend_body, &self.theme))
                 }
             }
         }
     }
 }
 
+enum BarMode {
+    /// Bars of each category are placed side by side
+    Grouped,
+    Stacked,
+}
+
+pub enum Orientation {
+    /// Categories along the x-axis
+    Vertical,
+    /// Categories along the y-axis
+    Horizontal,
+}
+
 struct BarColumn {
     x: f32,
     y: f32,
     dx: f32,
+    dy: f32,
     width: f32,
     height: f32,
     fill: String
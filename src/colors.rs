@@ -1,20 +0,0 @@
-//
-// rgb(237,10,63)
-// rgb(231,114,0)
-// rgb(254,216,93)
-// rgb(1,120,111)
-// rgb(165,7,44) // rgb(45,56,58)
-// rgb(243,184,127)
-// rgb(66,75,77) // rgb(94,4,25)
-// rgb(203,172,74)
-// rgb(153,201,197)
-// rgb(129,135,136)
-//
-// Separators
-// rgb(203,206,206)
-//
-// Axes
-// rgb(13,16,17)
-//
-// Text
-// rgb(12,12,12)
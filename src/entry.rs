@@ -1,13 +1,49 @@
 pub struct Entry {
     pub label: String,
-    pub value: i32
+    /// `None` when there is no data for the label
+    pub value: Option<f32>,
+    /// Seconds since the Unix epoch (UTC), places the entry on a time axis
+    pub time: Option<i64>,
 }
 
 impl Entry {
-    pub fn new<S>(label: S, value: i32) -> Entry where S: Into<String> {
+    /// Accepts both integer and floating-point values,
+    /// e.g. `Entry::new("Mon", 10)` or `Entry::new("Mon", 12.7)`.
+    pub fn new<S, V>(label: S, value: V) -> Entry
+        where S: Into<String>, V: Into<f64> {
+
         Entry {
             label: label.into(),
-            value: value
+            value: Some(value.into() as f32),
+            time: None,
+        }
+    }
+
+    /// Entry without a value, its label is still shown
+    pub fn missing<S>(label: S) -> Entry where S: Into<String> {
+        Entry {
+            label: label.into(),
+            value: None,
+            time: None,
+        }
+    }
+
+    /// Entry at the given Unix timestamp, the label is derived from
+    /// the time axis ticks.
+    pub fn at<V>(time: i64, value: V) -> Entry where V: Into<f64> {
+        Entry {
+            label: String::new(),
+            value: Some(value.into() as f32),
+            time: Some(time),
+        }
+    }
+
+    /// Entry at the given Unix timestamp without a value
+    pub fn missing_at(time: i64) -> Entry {
+        Entry {
+            label: String::new(),
+            value: None,
+            time: Some(time),
         }
     }
 }
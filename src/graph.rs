@@ -1,11 +1,12 @@
 use std::io;
 use std::io::prelude::*;
 use std::fs::File;
-use std::i32::{MAX, MIN};
+use std::f32::{MAX, MIN};
 
 use maud::PreEscaped;
 
 use entry::Entry;
+use series::Series;
 
 pub type HTML = PreEscaped<String>;
 
@@ -82,13 +83,85 @@ impl Tools {
         format!("translate({},{})", x, y)
     }
 
-    pub fn min_max_entry_values(entries: &Vec<Entry>) -> (i32, i32) {
+    /// Missing values are skipped
+    pub fn min_max_entry_values(entries: &Vec<Entry>) -> (f32, f32) {
         entries
             .iter()
-            .fold((MAX, MIN), |(min, max), ref e| {
-                let min = if e.value < min { e.value } else { min };
-                let max = if e.value > max { e.value } else { max };
+            .filter_map(|e| e.value)
+            .fold((MAX, MIN), |(min, max), v| {
+                let min = if v < min { v } else { min };
+                let max = if v > max { v } else { max };
                 (min, max)
             })
     }
+
+    pub fn min_max_series_values(series: &Vec<Series>) -> (f32, f32) {
+        series
+            .iter()
+            .map(|s| Tools::min_max_entry_values(&s.entries))
+            .fold((MAX, MIN), |(min, max), (s_min, s_max)| {
+                (min.min(s_min), max.max(s_max))
+            })
+    }
+
+    pub fn labels(entries: &Vec<Entry>) -> Vec<String> {
+        entries
+            .iter()
+            .map(|e| e.label.clone())
+            .collect()
+    }
+
+    /// Labels of the longest series
+    pub fn series_labels(series: &Vec<Series>) -> Vec<String> {
+        let longest = series
+            .iter()
+            .fold(None, |acc: Option<&Series>, s| match acc {
+                Some(l) if l.entries.len() >= s.entries.len() => Some(l),
+                _ => Some(s),
+            });
+
+        match longest {
+            Some(s) => Tools::labels(&s.entries),
+            None    => Vec::new(),
+        }
+    }
+
+    /// Format a value with 6 significant digits, which is about what
+    /// an `f32` holds. Trailing zeros are dropped so that `0.1 + 0.2`
+    /// is shown as `0.3` and `5.0` as `5`.
+    pub fn format_value(v: f32) -> String {
+        let magnitude = if v == 0.0 { 0 } else { v.abs().log10().floor() as i32 };
+        let decimals = (5 - magnitude).max(0) as usize;
+        let s = format!("{:.*}", decimals, v);
+        let s = if s.contains('.') {
+            s.trim_right_matches('0').trim_right_matches('.')
+        } else {
+            &s
+        };
+        match s {
+            "-0" => "0".to_string(),
+            s @ _ => s.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_value() {
+        assert_eq!(Tools::format_value(5.0), "5");
+        assert_eq!(Tools::format_value(-12.5), "-12.5");
+        assert_eq!(Tools::format_value(0.1 + 0.2), "0.3");
+        assert_eq!(Tools::format_value(-0.0), "0");
+        assert_eq!(Tools::format_value(16.7), "16.7");
+        assert_eq!(Tools::format_value(9620.3), "9620.3");
+        assert_eq!(Tools::format_value(81.0 * 0.2), "16.2");
+        assert_eq!(Tools::format_value(84.0 * 0.2), "16.8");
+        assert_eq!(Tools::format_value(1250000.0), "1250000");
+        assert_eq!(Tools::format_value(100.0), "100");
+        assert_eq!(Tools::format_value(-0.0000001), "-0.0000001");
+        assert_eq!(Tools::format_value(3.0 * 2e-7), "0.0000006");
+    }
 }
@@ -0,0 +1,165 @@
+/// How the points of a line are connected
+pub enum Interpolation {
+    /// Straight segments
+    Linear,
+    /// Cubic curve that doesn't overshoot the data, so a monotone
+    /// series stays monotone between the points
+    Monotone,
+    /// Cubic curve passing through every point
+    CatmullRom,
+    /// Value changes at the current point
+    StepBefore,
+    /// Value changes at the next point
+    StepAfter,
+    /// Value changes halfway between the points
+    StepMiddle,
+}
+
+impl Interpolation {
+    /// Path starting with a move to the first point
+    pub fn path(&self, points: &[(f32, f32)]) -> String {
+        match points.first() {
+            Some(&(x, y)) => format!("M{},{}{}", x, y, self.segments(points)),
+            None          => String::new(),
+        }
+    }
+
+    /// Commands connecting the points, without the initial move.
+    /// Used to continue a path, e.g. the bottom edge of an area.
+    pub fn segments(&self, points: &[(f32, f32)]) -> String {
+        let pairs = points.iter().zip(points.iter().skip(1));
+
+        match *self {
+            Interpolation::Linear     => pairs
+                .map(|(_, &(x, y))| format!("L{},{}", x, y))
+                .collect(),
+            Interpolation::StepBefore => pairs
+                .map(|(_, &(x, y))| format!("V{}H{}", y, x))
+                .collect(),
+            Interpolation::StepAfter  => pairs
+                .map(|(_, &(x, y))| format!("H{}V{}", x, y))
+                .collect(),
+            Interpolation::StepMiddle => pairs
+                .map(|(&(x0, _), &(x, y))| format!("H{}V{}H{}", (x0 + x) / 2.0, y, x))
+                .collect(),
+            Interpolation::Monotone   => {
+                let tangents = Interpolation::monotone_tangents(points);
+
+                pairs
+                    .zip(tangents.iter().zip(tangents.iter().skip(1)))
+                    .map(|((&(x0, y0), &(x1, y1)), (t0, t1))| {
+                        let h = (x1 - x0) / 3.0;
+                        format!("C{},{},{},{},{},{}",
+                                x0 + h, y0 + t0 * h, x1 - h, y1 - t1 * h, x1, y1)
+                    })
+                    .collect()
+            },
+            Interpolation::CatmullRom => {
+                let last = points.len().saturating_sub(1);
+
+                (0..last)
+                    .map(|i| {
+                        let (x0, y0) = points[if i > 0 { i - 1 } else { 0 }];
+                        let (x1, y1) = points[i];
+                        let (x2, y2) = points[i + 1];
+                        let (x3, y3) = points[if i + 2 <= last { i + 2 } else { last }];
+
+                        format!("C{},{},{},{},{},{}",
+                                x1 + (x2 - x0) / 6.0, y1 + (y2 - y0) / 6.0,
+                                x2 - (x3 - x1) / 6.0, y2 - (y3 - y1) / 6.0,
+                                x2, y2)
+                    })
+                    .collect()
+            },
+        }
+    }
+
+    /// Interpolation that draws the same curve through the points
+    /// in reverse order
+    pub fn reversed(&self) -> Interpolation {
+        match *self {
+            Interpolation::Linear     => Interpolation::Linear,
+            Interpolation::Monotone   => Interpolation::Monotone,
+            Interpolation::CatmullRom => Interpolation::CatmullRom,
+            Interpolation::StepBefore => Interpolation::StepAfter,
+            Interpolation::StepAfter  => Interpolation::StepBefore,
+            Interpolation::StepMiddle => Interpolation::StepMiddle,
+        }
+    }
+
+    /// Slope at every point, limited by the slopes of the neighbouring
+    /// segments (Steffen's method) to prevent overshooting
+    fn monotone_tangents(points: &[(f32, f32)]) -> Vec<f32> {
+        let slopes = points
+            .iter()
+            .zip(points.iter().skip(1))
+            .map(|(&(x0, y0), &(x1, y1))| {
+                if x1 == x0 { 0.0 } else { (y1 - y0) / (x1 - x0) }
+            })
+            .collect::<Vec<f32>>();
+
+        (0..points.len())
+            .map(|i| {
+                if slopes.is_empty() {
+                    0.0
+                } else if i == 0 {
+                    slopes[0]
+                } else if i == slopes.len() {
+                    slopes[i - 1]
+                } else {
+                    let (s0, s1) = (slopes[i - 1], slopes[i]);
+                    let h0 = points[i].0 - points[i - 1].0;
+                    let h1 = points[i + 1].0 - points[i].0;
+                    let p = if h0 + h1 == 0.0 { 0.0 } else { (s0 * h1 + s1 * h0) / (h0 + h1) };
+
+                    (Interpolation::sign(s0) + Interpolation::sign(s1))
+                        * s0.abs().min(s1.abs()).min(0.5 * p.abs())
+                }
+            })
+            .collect()
+    }
+
+    fn sign(v: f32) -> f32 {
+        if v > 0.0 {
+            1.0
+        } else if v < 0.0 {
+            -1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path() {
+        let points = [(0.0, 0.0), (10.0, 10.0), (20.0, 0.0)];
+
+        assert_eq!(Interpolation::Linear.path(&points), "M0,0L10,10L20,0");
+        assert_eq!(Interpolation::StepBefore.path(&points), "M0,0V10H10V0H20");
+        assert_eq!(Interpolation::StepAfter.path(&points), "M0,0H10V10H20V0");
+        assert_eq!(Interpolation::StepMiddle.path(&points), "M0,0H5V10H10H15V0H20");
+        assert_eq!(Interpolation::Linear.path(&[]), "");
+        assert_eq!(Interpolation::Monotone.path(&[(1.0, 2.0)]), "M1,2");
+    }
+
+    #[test]
+    fn monotone() {
+        // Flat tangent at a local maximum keeps the curve below it
+        let points = [(0.0, 0.0), (10.0, 10.0), (20.0, 0.0)];
+        assert_eq!(Interpolation::monotone_tangents(&points), vec![1.0, 0.0, -1.0]);
+
+        let points = [(0.0, 0.0), (10.0, 10.0), (20.0, 40.0)];
+        assert_eq!(Interpolation::monotone_tangents(&points), vec![1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn catmull_rom() {
+        let points = [(0.0, 0.0), (6.0, 6.0), (12.0, 0.0)];
+        assert_eq!(Interpolation::CatmullRom.path(&points), "M0,0C1,1,4,6,6,6C8,6,11,1,12,0");
+    }
+}
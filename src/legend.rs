@@ -0,0 +1,335 @@
+use graph::{Tools, Coord, HTML};
+use theme::Theme;
+use text::TextMetrics;
+use marker::Marker;
+
+const ROW_HEIGHT: f32 = 20.0;
+const ITEM_SPACING: f32 = 15.0;
+/// Space between the legend and the graph
+const MARGIN: f32 = 20.0;
+/// Size of the marker on a line swatch
+const SWATCH_MARKER_SIZE: f32 = 6.0;
+
+/// Legend that can be attached to any graph. Items are filled in
+/// by the graph itself.
+pub struct Legend {
+    position: LegendPosition,
+    flow: Flow,
+    wrap: bool,
+    items: Vec<LegendItem>,
+}
+
+impl Legend {
+    pub fn new() -> Legend {
+        Legend {
+            position: LegendPosition::Right,
+            flow: Flow::Vertical,
+            wrap: true,
+            items: Vec::new(),
+        }
+    }
+
+    pub fn position(mut self, position: LegendPosition) -> Legend {
+        self.position = position;
+        self
+    }
+
+    pub fn flow(mut self, flow: Flow) -> Legend {
+        self.flow = flow;
+        self
+    }
+
+    /// Start a new row (or column) when items don't fit
+    pub fn wrap(mut self, wrap: bool) -> Legend {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn items(mut self, items: Vec<LegendItem>) -> Legend {
+        self.items = items;
+        self
+    }
+
+    /// Reserve space for an optional legend, returns the area left
+    /// for the graph and the legend with its box
+    pub fn layout(legend: Option<Legend>, area: Coord,
+                  theme: &Theme) -> (Coord, Option<(Legend, Coord)>) {
+        match legend {
+            Some(l) => {
+                let (graph, body) = l.split(&area, theme);
+                (graph, Some((l, body)))
+            },
+            None    => (area, None),
+        }
+    }
+
+    /// Split the area into the part left for the graph and the legend box
+    pub fn split(&self, area: &Coord, theme: &Theme) -> (Coord, Coord) {
+        let (w, h) = self.size(self.max_size(area), theme.font_size);
+
+        match self.position {
+            LegendPosition::Top    => (
+                Coord { y: area.y + h + MARGIN, height: area.height - h - MARGIN, .. *area },
+                Coord { x: area.x, y: area.y, width: area.width, height: h },
+            ),
+            LegendPosition::Bottom => (
+                Coord { height: area.height - h - MARGIN, .. *area },
+                Coord { x: area.x, y: area.y + area.height - h, width: area.width, height: h },
+            ),
+            LegendPosition::Left   => (
+                Coord { x: area.x + w + MARGIN, width: area.width - w - MARGIN, .. *area },
+                Coord { x: area.x, y: area.y, width: w, height: area.height },
+            ),
+            LegendPosition::Right  => (
+                Coord { width: area.width - w - MARGIN, .. *area },
+                Coord { x: area.x + area.width - w, y: area.y, width: w, height: area.height },
+            ),
+            LegendPosition::Inside => {
+                let inset = 10.0;
+                (
+                    Coord { .. *area },
+                    Coord { x: area.x + area.width - w - inset, y: area.y + inset, width: w, height: h },
+                )
+            },
+        }
+    }
+
+    /// Render the legend into the box returned by `split`
+    pub fn render(&self, area: &Coord, theme: &Theme) -> HTML {
+        let positions = self.positions((area.width, area.height), theme.font_size);
+
+        html! {
+            g.legend transform=(Tools::tr(area.x, area.y)) {
+                @if let LegendPosition::Inside = self.position {
+                    rect.legend-background x="-5" y="-5" width=(area.width + 10.0) height=(area.height + 10.0) fill=(theme.legend_background) fill-opacity="0.8" {}
+                }
+
+                @for (item, &(x, y)) in self.items.iter().zip(positions.iter()) {
+                    g.legend-item transform=(Tools::tr(x, y)) {
+                        (item.swatch.render(&item.color, theme))
+                        text x=(item.swatch.width() + 6.0) y="11" text-anchor="start" fill=(theme.text_color) (item.text)
+                    }
+                }
+            }
+        }
+    }
+
+    fn max_size(&self, area: &Coord) -> (f32, f32) {
+        match self.position {
+            LegendPosition::Top | LegendPosition::Bottom => (area.width, area.height / 3.0),
+            LegendPosition::Left | LegendPosition::Right => (area.width / 3.0, area.height),
+            LegendPosition::Inside => (area.width / 2.0, area.height / 2.0),
+        }
+    }
+
+    fn size(&self, max: (f32, f32), font_size: f32) -> (f32, f32) {
+        self.positions(max, font_size)
+            .iter()
+            .zip(self.items.iter())
+            .fold((0.0, 0.0), |(w, h): (f32, f32), (&(x, y), item)| {
+                (w.max(x + item.width(font_size)), h.max(y + ROW_HEIGHT))
+            })
+    }
+
+    /// Top left corner of each item
+    fn positions(&self, (max_w, max_h): (f32, f32), font_size: f32) -> Vec<(f32, f32)> {
+        let mut positions = Vec::with_capacity(self.items.len());
+        let (mut x, mut y) = (0.0, 0.0);
+        // Widest item of the current column for the vertical flow
+        let mut column_w: f32 = 0.0;
+
+        for item in self.items.iter() {
+            let w = item.width(font_size);
+
+            match self.flow {
+                Flow::Horizontal => {
+                    if self.wrap && x > 0.0 && x + w > max_w {
+                        x = 0.0;
+                        y += ROW_HEIGHT;
+                    }
+                    positions.push((x, y));
+                    x += w + ITEM_SPACING;
+                },
+                Flow::Vertical   => {
+                    if self.wrap && y > 0.0 && y + ROW_HEIGHT > max_h {
+                        x += column_w + ITEM_SPACING;
+                        y = 0.0;
+                        column_w = 0.0;
+                    }
+                    positions.push((x, y));
+                    y += ROW_HEIGHT;
+                    column_w = column_w.max(w);
+                },
+            }
+        }
+
+        positions
+    }
+}
+
+pub struct LegendItem {
+    pub text: String,
+    pub color: String,
+    pub swatch: Swatch,
+}
+
+impl LegendItem {
+    fn width(&self, font_size: f32) -> f32 {
+        self.swatch.width() + 6.0 + TextMetrics::width(&self.text, font_size)
+    }
+}
+
+/// Nested reference circles explaining the bubble sizes,
+/// placed to the right of the graph
+pub struct SizeLegend {
+    /// Label and diameter of each reference bubble
+    bubbles: Vec<(String, f32)>,
+}
+
+impl SizeLegend {
+    pub fn new(bubbles: Vec<(String, f32)>) -> SizeLegend {
+        SizeLegend {
+            bubbles: bubbles,
+        }
+    }
+
+    fn diameter(&self) -> f32 {
+        self.bubbles.iter().fold(0.0, |acc: f32, &(_, d)| acc.max(d))
+    }
+
+    /// Split the area into the part left for the graph and the legend box
+    pub fn split(&self, area: &Coord, theme: &Theme) -> (Coord, Coord) {
+        let d = self.diameter();
+        let labels = TextMetrics::max_width(self.bubbles.iter().map(|&(ref l, _)| l), theme.font_size);
+        let w = d + 8.0 + labels;
+
+        (
+            Coord { width: area.width - w - MARGIN, .. *area },
+            Coord { x: area.x + area.width - w, y: area.y, width: w, height: d },
+        )
+    }
+
+    pub fn render(&self, area: &Coord, theme: &Theme) -> HTML {
+        let d = self.diameter();
+
+        html! {
+            g.size-legend transform=(Tools::tr(area.x, area.y)) {
+                @for &(ref label, size) in self.bubbles.iter() {
+                    circle cx=(d / 2.0) cy=(d - size / 2.0) r=(size / 2.0) fill="none" stroke=(theme.text_color) {}
+                    line x1=(d / 2.0) x2=(d + 6.0) y1=(d - size) y2=(d - size) stroke=(theme.text_color) stroke-dasharray="2,2" {}
+                    text x=(d + 8.0) y=(d - size + 4.0) text-anchor="start" fill=(theme.text_color) (label)
+                }
+            }
+        }
+    }
+}
+
+/// Where the legend is placed relative to the graph
+pub enum LegendPosition {
+    Top,
+    Right,
+    Bottom,
+    Left,
+    /// Top right corner over the graph
+    Inside,
+}
+
+/// Direction in which legend items are laid out
+pub enum Flow {
+    Horizontal,
+    Vertical,
+}
+
+/// Mark drawn next to the legend text, matches the series mark
+pub enum Swatch {
+    /// Filled box, used for bars and pie slices
+    Rect,
+    /// Line segment with the marker of the series if it has one,
+    /// used for line series
+    Line(Option<Marker>),
+}
+
+impl Swatch {
+    fn width(&self) -> f32 {
+        match *self {
+            Swatch::Rect    => 12.0,
+            Swatch::Line(_) => 16.0,
+        }
+    }
+
+    fn render(&self, color: &str, theme: &Theme) -> HTML {
+        match *self {
+            Swatch::Rect             => html! {
+                rect.swatch fill=(color) x="0" y="0" width="12" height="12" {}
+            },
+            Swatch::Line(ref marker) => html! {
+                line.swatch x1="0" x2="16" y1="6" y2="6" stroke=(color) stroke-width=(theme.line_width) {}
+                @if let Some(ref marker) = *marker {
+                    (marker.render(8.0, 6.0, SWATCH_MARKER_SIZE, color, None))
+                }
+            },
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(n: usize) -> Vec<LegendItem> {
+        (0..n)
+            .map(|_| LegendItem {
+                text: "IlIlIlIl".to_string(),
+                color: "red".to_string(),
+                swatch: Swatch::Rect,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn positions() {
+        // At 14px each item is 12 + 6 + 28 = 46px wide
+        let legend = Legend::new().flow(Flow::Horizontal).items(items(3));
+        assert_eq!(legend.positions((120.0, 100.0), 14.0),
+                   vec![(0.0, 0.0), (61.0, 0.0), (0.0, 20.0)]);
+
+        let legend = Legend::new().flow(Flow::Vertical).items(items(3));
+        assert_eq!(legend.positions((100.0, 50.0), 14.0),
+                   vec![(0.0, 0.0), (0.0, 20.0), (61.0, 0.0)]);
+
+        let legend = Legend::new().flow(Flow::Vertical).wrap(false).items(items(3));
+        assert_eq!(legend.positions((100.0, 50.0), 14.0),
+                   vec![(0.0, 0.0), (0.0, 20.0), (0.0, 40.0)]);
+    }
+
+    #[test]
+    fn split() {
+        let theme = Theme { font_size: 14.0, .. Theme::light() };
+        let area = Coord { x: 10.0, y: 10.0, width: 400.0, height: 300.0 };
+        let legend = Legend::new().items(items(2));
+        let (graph, legend_box) = legend.split(&area, &theme);
+        assert_eq!((graph.x, graph.width), (10.0, 400.0 - 46.0 - 20.0));
+        assert_eq!((legend_box.x, legend_box.width), (10.0 + 400.0 - 46.0, 46.0));
+
+        let legend = Legend::new()
+            .position(LegendPosition::Bottom)
+            .flow(Flow::Horizontal)
+            .items(items(2));
+        let (graph, legend_box) = legend.split(&area, &theme);
+        assert_eq!(graph.height, 300.0 - 20.0 - 20.0);
+        assert_eq!(legend_box.y, 10.0 + 300.0 - 20.0);
+    }
+
+    #[test]
+    fn inside_background() {
+        let area = Coord { x: 0.0, y: 0.0, width: 100.0, height: 40.0 };
+        let legend = Legend::new().position(LegendPosition::Inside).items(items(2));
+        let theme = Theme { legend_background: "rgb(1,2,3)".to_string(), .. Theme::dark() };
+        let svg = legend.render(&area, &theme).into_string();
+        assert!(svg.contains("fill=\"rgb(1,2,3)\""));
+
+        let svg = Legend::new().items(items(2)).render(&area, &theme).into_string();
+        assert!(!svg.contains("legend-background"));
+    }
+}
@@ -12,12 +12,30 @@ mod pie;
 mod entry;
 mod scale;
 mod axis;
+mod theme;
+mod legend;
+mod title;
+mod series;
+mod time;
+mod text;
+mod point;
+mod marker;
+mod scatter;
+mod interpolation;
 
 pub use graph::Graph;
 pub use entry::Entry;
-pub use bar::{BarBuilder, Bar};
+pub use series::{Series, Gaps, YAxis};
+pub use point::Point;
+pub use marker::Marker;
+pub use bar::{BarBuilder, Bar, Orientation};
 pub use line::{LineBuilder, Line};
-pub use pie::{PieBuilder, Pie};
+pub use interpolation::Interpolation;
+pub use pie::{PieBuilder, Pie, Direction, PieLabels, SliceText};
+pub use scatter::{ScatterBuilder, Scatter};
+pub use scale::{ScaleKind, ScaleOptions};
+pub use legend::{Legend, LegendPosition, Flow};
+pub use theme::Theme;
 
 //#[cfg(test)]
 //mod tests {
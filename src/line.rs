@@ -1,20 +1,67 @@
+use std::f32::{MAX, MIN};
+
 use graph::{Graph, Tools, Coord, Padding, HTML, Size};
 use entry::Entry;
-use axis::{Axes2d, LabelPosition, AxisOption};
-use scale::{LinearScale, LinearRoundedScale};
+use series::{Series, Gaps, YAxis};
+use axis::{Axes2d, LabelPosition, AxisOption, AxisKind};
+use scale::{Scale, LinearScale, ScaleKind, ScaleOptions};
+use time::TimeScale;
+use title::Title;
+use theme::Theme;
+use legend::{Legend, LegendItem, Swatch};
+use interpolation::Interpolation;
+use marker::Marker;
+use text::TextMetrics;
 
 pub struct LineBuilder {
     width: f32,
     height: f32,
-    entries: Option<Vec<Entry>>,
+    theme: Theme,
+    title: Option<String>,
+    subtitle: Option<String>,
+    x_title: Option<String>,
+    y_title: Option<String>,
+    y_scale: ScaleOptions,
+    y2_title: Option<String>,
+    y2_scale: ScaleOptions,
+    mode: LineMode,
+    area: Option<f32>,
+    interpolation: Interpolation,
+    marker: Option<Marker>,
+    marker_size: f32,
+    marker_fill: Option<String>,
+    marker_stroke: Option<String>,
+    data_labels: bool,
+    legend: Option<Legend>,
+    series: Vec<Series>,
 }
 
+/// Fill opacity of stacked areas unless set with `area`
+const STACKED_OPACITY: f32 = 0.7;
+
 impl LineBuilder {
     pub fn new() -> LineBuilder {
         LineBuilder {
             width: 500.0,
             height: 500.0,
-            entries: None,
+            theme: Theme::light(),
+            title: None,
+            subtitle: None,
+            x_title: None,
+            y_title: None,
+            y_scale: ScaleOptions::new(ScaleKind::Linear),
+            y2_title: None,
+            y2_scale: ScaleOptions::new(ScaleKind::Linear),
+            mode: LineMode::Overlap,
+            area: None,
+            interpolation: Interpolation::Linear,
+            marker: None,
+            marker_size: 6.0,
+            marker_fill: None,
+            marker_stroke: None,
+            data_labels: false,
+            legend: None,
+            series: Vec::new(),
         }
     }
 
@@ -28,100 +75,571 @@ impl LineBuilder {
         self
     }
 
+    /// Set the data as a single unnamed series, replaces the series
+    /// added before
     pub fn entries(mut self, entries: Vec<Entry>) -> LineBuilder {
-        self.entries = Some(entries);
+        self.series = vec![Series::new("", entries)];
+        self
+    }
+
+    /// Add a series which is drawn as a separate line
+    pub fn series(mut self, series: Series) -> LineBuilder {
+        self.series.push(series);
+        self
+    }
+
+    /// Fill the area between each line and the baseline
+    /// of the value axis
+    pub fn area(mut self, opacity: f32) -> LineBuilder {
+        self.area = Some(opacity);
+        self
+    }
+
+    /// Stack the series areas on top of each other,
+    /// entries are matched by their index
+    pub fn stacked(mut self) -> LineBuilder {
+        self.mode = LineMode::Stacked;
+        self.area = Some(self.area.unwrap_or(STACKED_OPACITY));
+        self
+    }
+
+    /// Stacked areas where every index adds up to 100%
+    pub fn normalized(mut self) -> LineBuilder {
+        self.mode = LineMode::Normalized;
+        self.area = Some(self.area.unwrap_or(STACKED_OPACITY));
+        self
+    }
+
+    /// How the points are connected, straight lines by default
+    pub fn interpolation(mut self, interpolation: Interpolation) -> LineBuilder {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Draw a marker at every point
+    pub fn markers(mut self, marker: Marker) -> LineBuilder {
+        self.marker = Some(marker);
+        self
+    }
+
+    pub fn marker_size(mut self, size: f32) -> LineBuilder {
+        self.marker_size = size;
+        self
+    }
+
+    /// Marker fill, the series color by default
+    pub fn marker_fill<S>(mut self, color: S) -> LineBuilder where S: Into<String> {
+        self.marker_fill = Some(color.into());
+        self
+    }
+
+    /// Marker outline, none by default
+    pub fn marker_stroke<S>(mut self, color: S) -> LineBuilder where S: Into<String> {
+        self.marker_stroke = Some(color.into());
+        self
+    }
+
+    /// Show the values above the points, labels overlapping
+    /// earlier ones are left out
+    pub fn data_labels(mut self) -> LineBuilder {
+        self.data_labels = true;
+        self
+    }
+
+    /// Legend with a line per series, shown by default
+    /// when there is more than one series
+    pub fn legend(mut self, legend: Legend) -> LineBuilder {
+        self.legend = Some(legend);
+        self
+    }
+
+    /// Scale of the value axis, linear by default
+    pub fn y_scale<S>(mut self, y_scale: S) -> LineBuilder where S: Into<ScaleOptions> {
+        self.y_scale = y_scale.into();
+        self
+    }
+
+    /// Scale of the right value axis, which is shown when a series
+    /// is bound to it with `Series::y_axis(YAxis::Right)`
+    pub fn y2_scale<S>(mut self, y2_scale: S) -> LineBuilder where S: Into<ScaleOptions> {
+        self.y2_scale = y2_scale.into();
+        self
+    }
+
+    /// Colors and fonts, `Theme::light()` by default
+    pub fn theme(mut self, theme: Theme) -> LineBuilder {
+        self.theme = theme;
+        self
+    }
+
+    pub fn title<S>(mut self, title: S) -> LineBuilder where S: Into<String> {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn subtitle<S>(mut self, subtitle: S) -> LineBuilder where S: Into<String> {
+        self.subtitle = Some(subtitle.into());
+        self
+    }
+
+    pub fn x_title<S>(mut self, title: S) -> LineBuilder where S: Into<String> {
+        self.x_title = Some(title.into());
+        self
+    }
+
+    /// Title drawn rotated along the y-axis
+    pub fn y_title<S>(mut self, title: S) -> LineBuilder where S: Into<String> {
+        self.y_title = Some(title.into());
+        self
+    }
+
+    /// Title drawn rotated along the right value axis
+    pub fn y2_title<S>(mut self, title: S) -> LineBuilder where S: Into<String> {
+        self.y2_title = Some(title.into());
         self
     }
 
     pub fn build(self) -> Line {
         let padding = Padding::with_same(15.0);
+        let theme = self.theme;
         let (width, height) = (self.width, self.height);
+        let series = self.series;
+        let area = self.area;
+        let marker = self.marker;
+        let items = series
+            .iter()
+            .enumerate()
+            .map(|(i, s)| LegendItem {
+                text: s.name.clone(),
+                color: s.color_or_palette(&theme, i),
+                swatch: if area.is_some() { Swatch::Rect } else { Swatch::Line(marker) },
+            })
+            .collect();
+        let legend = match self.legend {
+            Some(l)                  => Some(l),
+            None if series.len() > 1 => Some(Legend::new()),
+            None                     => None,
+        }.map(|l| l.items(items));
         let content = Coord::from_padding(&padding, (0.0, 0.0, width, height));
-        let entries = match self.entries {
-            Some(e) => e,
-            None    => Vec::with_capacity(0),
-        };
+        let title = Title::new(self.title, self.subtitle);
+        let (content, title_body) = title.split(&content);
+        let (content, legend) = Legend::layout(legend, content, &theme);
+        let time_range = LineBuilder::time_range(&series);
+        let stack = Line::stack(&series, &self.mode);
 
         let axes = {
-            let x_opt = AxisOption {
-                scale: Box::new(
-                    LinearScale::new(0.0, entries.len() as f32)
-                ),
-                label_position: LabelPosition::Normal,
+            let x_opt = match time_range {
+                Some((start, stop)) => AxisOption {
+                    scale: Box::new(TimeScale::new(start, stop)),
+                    kind: AxisKind::Value,
+                    title: self.x_title,
+                    label_position: LabelPosition::Normal,
+                },
+                None => {
+                    let labels = Tools::series_labels(&series);
+
+                    AxisOption {
+                        scale: Box::new(
+                            LinearScale::new(0.0, labels.len() as f32)
+                        ),
+                        kind: AxisKind::Category(labels),
+                        title: self.x_title,
+                        label_position: LabelPosition::Normal,
+                    }
+                },
             };
 
             let y_opt = {
-                let (min, max) = {
-                    let (min, max) = Tools::min_max_entry_values(&entries);
-                    ((min as f32).min(0.0), (max as f32).max(0.0))
-                };
+                let (min, max) = LineBuilder::min_max(&self.mode, &series, &stack, false);
 
                 AxisOption {
-                    scale: Box::new(
-                        LinearRoundedScale::new(min, max)
-                    ),
+                    scale: self.y_scale.scale(min, max),
+                    kind: AxisKind::Value,
+                    title: self.y_title,
                     label_position: LabelPosition::Normal,
                 }
             };
 
-            Axes2d::new((content.width, content.height), x_opt, y_opt)
+            let y2_opt = if series.iter().any(|s| s.y_axis.is_right()) {
+                let (min, max) = LineBuilder::min_max(&self.mode, &series, &stack, true);
+
+                Some(AxisOption {
+                    scale: self.y2_scale.scale(min, max),
+                    kind: AxisKind::Value,
+                    title: self.y2_title,
+                    label_position: LabelPosition::Normal,
+                })
+            } else {
+                None
+            };
+
+            Axes2d::with_y2((content.width, content.height), x_opt, y_opt, y2_opt, &theme)
         };
 
         let body = axes.body();
 
         Line {
             size: Size { width: width, height: height },
-            padding: padding,
+            title: (title, title_body),
+            theme: theme,
+            content: content,
+            legend: legend,
             axes: axes,
             body: body,
-            entries: entries,
+            series: series,
+            stack: stack,
+            mode: self.mode,
+            area: self.area,
+            interpolation: self.interpolation,
+            marker: self.marker,
+            marker_size: self.marker_size,
+            marker_fill: self.marker_fill,
+            marker_stroke: self.marker_stroke,
+            data_labels: self.data_labels,
+            time_origin: time_range.map(|(start, _)| start),
+        }
+    }
+
+    /// Value range of the series drawn against the left
+    /// or the right axis
+    fn min_max(mode: &LineMode, series: &Vec<Series>,
+               stack: &Vec<Vec<Option<(f32, f32)>>>, right: bool) -> (f32, f32) {
+        let bound = series
+            .iter()
+            .zip(stack.iter())
+            .filter(|&(s, _)| s.y_axis.is_right() == right);
+
+        match *mode {
+            LineMode::Overlap    => bound
+                .map(|(s, _)| Tools::min_max_entry_values(&s.entries))
+                .fold((MAX, MIN), |(min, max), (s_min, s_max)| {
+                    (min.min(s_min), max.max(s_max))
+                }),
+            LineMode::Stacked    => bound
+                .flat_map(|(_, s)| s.iter().filter_map(|v| *v))
+                .fold((0.0, 0.0), |(min, max): (f32, f32), (from, to)| {
+                    (min.min(from).min(to), max.max(from).max(to))
+                }),
+            LineMode::Normalized => (0.0, 100.0),
         }
     }
+
+    /// First and last timestamps if every entry has one
+    fn time_range(series: &Vec<Series>) -> Option<(i64, i64)> {
+        let mut range = None;
+
+        for e in series.iter().flat_map(|s| s.entries.iter()) {
+            range = match (e.time, range) {
+                (None, _)                     => return None,
+                (Some(t), None)               => Some((t, t)),
+                (Some(t), Some((start, stop))) => Some((start.min(t), stop.max(t))),
+            };
+        }
+
+        range
+    }
 }
 
 pub struct Line {
     size: Size,
-    entries: Vec<Entry>,
-    padding: Padding,
+    title: (Title, Coord),
+    theme: Theme,
+    series: Vec<Series>,
+    content: Coord,
+    legend: Option<(Legend, Coord)>,
     body: Coord,
     axes: Axes2d,
+    /// Bottom and top value of every entry, `None` for gaps
+    stack: Vec<Vec<Option<(f32, f32)>>>,
+    mode: LineMode,
+    area: Option<f32>,
+    interpolation: Interpolation,
+    marker: Option<Marker>,
+    marker_size: f32,
+    marker_fill: Option<String>,
+    marker_stroke: Option<String>,
+    data_labels: bool,
+    time_origin: Option<i64>,
 }
 
 impl Line {
-    fn line_path(&self) -> String {
+    /// Bottom and top value of each entry, series are stacked
+    /// on the running total of the previous ones on the same axis
+    fn stack(series: &Vec<Series>, mode: &LineMode) -> Vec<Vec<Option<(f32, f32)>>> {
+        let values = series
+            .iter()
+            .map(|s| (s.y_axis.is_right(), s.values()))
+            .collect::<Vec<_>>();
+        let len = values
+            .iter()
+            .fold(0, |acc, &(_, ref v)| acc.max(v.len()));
+        let totals = |right: bool| (0..len)
+            .map(|i| {
+                values
+                    .iter()
+                    .filter(|&&(r, _)| r == right)
+                    .filter_map(|&(_, ref v)| v.get(i).and_then(|v| *v))
+                    .fold(0.0, |acc, v| acc + v.abs())
+            })
+            .collect::<Vec<f32>>();
+        let totals = [totals(false), totals(true)];
+        let mut tops = [vec![0.0; len], vec![0.0; len]];
+
+        values
+            .iter()
+            .map(|&(right, ref v)| {
+                let side = if right { 1 } else { 0 };
+                let (totals, tops) = (&totals[side], &mut tops[side]);
+
+                v.iter()
+                    .enumerate()
+                    .map(|(i, v)| v.map(|v| {
+                        let value = match *mode {
+                            LineMode::Normalized if totals[i] > 0.0 => {
+                                v / totals[i] * 100.0
+                            },
+                            LineMode::Normalized => 0.0,
+                            _                    => v,
+                        };
+
+                        match *mode {
+                            LineMode::Overlap => (0.0, value),
+                            _                 => {
+                                let from = tops[i];
+                                tops[i] = from + value;
+                                (from, from + value)
+                            },
+                        }
+                    }))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Scale of the axis the series is bound to
+    fn scale(&self, j: usize) -> &Box<Scale> {
+        match (&self.series[j].y_axis, &self.axes.y2) {
+            (&YAxis::Right, &Some(ref axis)) => &axis.scale,
+            _                                => &self.axes.y.scale,
+        }
+    }
+
+    fn points(&self, j: usize) -> Vec<Option<(f32, f32, f32)>> {
         let h = self.body.height;
+        let scale = self.scale(j);
+
+        self.series[j].entries
+            .iter()
+            .zip(self.stack[j].iter())
+            .enumerate()
+            .map(|(i, (e, v))| v.map(|(from, to)| {
+                let from = match self.mode {
+                    LineMode::Overlap => scale.baseline(),
+                    _                 => from,
+                };
+
+                (self.x(i, e), h - scale.offset(from), h - scale.offset(to))
+            }))
+            .collect()
+    }
+
+    /// Parts of the line between gaps, a single part
+    /// if the series connects across them
+    fn runs(&self, j: usize) -> Vec<Vec<(f32, f32, f32)>> {
+        let mut runs = vec![Vec::new()];
+
+        for p in self.points(j) {
+            match (p, &self.series[j].gaps) {
+                (Some(p), _)         => runs.last_mut().unwrap().push(p),
+                (None, &Gaps::Break) => runs.push(Vec::new()),
+                (None, _)            => {},
+            }
+        }
+
+        runs.into_iter().filter(|r| !r.is_empty()).collect()
+    }
+
+    fn line_path(&self, points: &Vec<(f32, f32, f32)>) -> String {
+        let top = points
+            .iter()
+            .map(|&(x, _, y)| (x, y))
+            .collect::<Vec<_>>();
+
+        self.interpolation.path(&top)
+    }
+
+    /// Line path followed by the bottom edge drawn backwards
+    fn area_path(&self, points: &Vec<(f32, f32, f32)>) -> String {
+        let bottom = points
+            .iter()
+            .rev()
+            .map(|&(x, y, _)| (x, y))
+            .collect::<Vec<_>>();
+
+        match bottom.first() {
+            Some(&(x, y)) => format!("{}L{},{}{}Z",
+                                     self.line_path(points), x, y,
+                                     self.interpolation.reversed().segments(&bottom)),
+            None          => String::new(),
+        }
+    }
 
-        self.entries
+    fn paths(&self) -> Vec<LinePath> {
+        self.series
             .iter()
             .enumerate()
-            .map(|(i, e)| (self.axes.x.scale.offset(i as f32),
-                           h - self.axes.y.scale.offset(e.value as f32)))
-            .fold("".to_string(), |acc, (x, y)| {
-                let op = if acc.is_empty() {
-                    "M"
-                } else {
-                    "L"
+            .map(|(i, s)| {
+                let runs = self.runs(i);
+
+                LinePath {
+                    d: runs.iter().map(|r| self.line_path(r)).collect::<Vec<_>>().concat(),
+                    area: self.area.map(|_| {
+                        runs.iter().map(|r| self.area_path(r)).collect::<Vec<_>>().concat()
+                    }),
+                    stroke: s.color_or_palette(&self.theme, i),
+                }
+            })
+            .collect()
+    }
+
+    fn markers(&self) -> Vec<HTML> {
+        let marker = match self.marker {
+            Some(ref m) => m,
+            None        => return Vec::new(),
+        };
+        let stroke = self.marker_stroke.as_ref().map(|s| s.as_str());
+
+        self.series
+            .iter()
+            .enumerate()
+            .flat_map(|(j, s)| {
+                let fill = match self.marker_fill {
+                    Some(ref f) => f.clone(),
+                    None        => s.color_or_palette(&self.theme, j),
                 };
-                format!("{}{}{},{}", acc, op, x, y)
+
+                self.points(j)
+                    .into_iter()
+                    .filter_map(|p| p)
+                    .map(move |(x, _, y)| marker.render(x, y, self.marker_size, &fill, stroke))
+            })
+            .collect()
+    }
+
+    /// Value labels above the points, thinned out so they don't overlap
+    fn data_labels(&self) -> Vec<DataLabel> {
+        if !self.data_labels {
+            return Vec::new();
+        }
+
+        let font_size = self.theme.font_size;
+        let gap = if self.marker.is_some() { self.marker_size / 2.0 } else { 0.0 } + 4.0;
+        let labels = self.series
+            .iter()
+            .enumerate()
+            .flat_map(|(j, s)| {
+                s.values()
+                    .into_iter()
+                    .zip(self.points(j).into_iter())
+                    .filter_map(|(v, p)| match (v, p) {
+                        (Some(v), Some((x, _, y))) => Some(DataLabel {
+                            x: x,
+                            y: y - gap,
+                            text: Tools::format_value(v),
+                        }),
+                        _                          => None,
+                    })
+                    .collect::<Vec<_>>()
             })
+            .collect::<Vec<DataLabel>>();
+        let boxes = labels
+            .iter()
+            .map(|l| {
+                let width = TextMetrics::width(&l.text, font_size);
+                Coord { x: l.x - width / 2.0, y: l.y - font_size, width: width, height: font_size }
+            })
+            .collect::<Vec<Coord>>();
+        let kept = TextMetrics::thin(&boxes);
+
+        labels
+            .into_iter()
+            .enumerate()
+            .filter(|&(i, _)| kept.contains(&i))
+            .map(|(_, l)| l)
+            .collect()
+    }
+
+    /// Entries are placed by their timestamp on a time axis
+    /// and by their index otherwise
+    fn x(&self, i: usize, e: &Entry) -> f32 {
+        match (self.time_origin, e.time) {
+            (Some(origin), Some(t)) => self.axes.x.scale.offset((t - origin) as f32),
+            _                       => self.axes.x.scale.offset(i as f32),
+        }
     }
 }
 
 impl Graph for Line {
     fn into_html(&self) -> HTML {
-        let line = self.line_path();
+        let paths = self.paths();
+        let markers = self.markers();
+        let labels = self.data_labels();
 
         html! {
-            svg width=(self.size.width) height=(self.size.height) xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" {
+            svg width=(self.size.width) height=(self.size.height) xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" font-family=(self.theme.font_family) font-size=(self.theme.font_size) {
+
+                (self.theme.background(&self.size))
+                (self.title.0.render(&self.title.1, &self.theme))
 
-                g.content transform=(Tools::tr(self.padding.left, self.padding.top)) {
-                    (self.axes.render(&self.entries))
+                g.content transform=(Tools::tr(self.content.x, self.content.y)) {
+                    (self.axes.render())
 
                     g.line-box transform=(Tools::tr(self.body.x, self.body.y)) {
-                        path.line fill="none" stroke-width="2" stroke="rgb(1,120,111)" d=(line) {}
+                        @for p in paths.iter() {
+                            @if let Some(ref area) = p.area {
+                                path.area stroke="none" fill=(p.stroke) fill-opacity=(self.area.unwrap_or(1.0)) d=(area) {}
+                            }
+                        }
+
+                        @for LinePath { d, stroke, .. } in paths {
+                            path.line fill="none" stroke-width=(self.theme.line_width) stroke=(stroke) d=(d) {}
+                        }
+
+                        @for marker in markers {
+                            (marker)
+                        }
+
+                        @for DataLabel { x, y, text } in labels {
+                            text.data-label x=(x) y=(y) text-anchor="middle" fill=(self.theme.text_color) (text)
+                        }
                     }
                 }
+
+                @if let Some((ref legend, ref legend_body)) = self.legend {
+                    (legend.render(legend_body, &self.theme))
+                }
             }
         }
     }
 }
+
+enum LineMode {
+    /// Every series is drawn from the baseline
+    Overlap,
+    Stacked,
+    /// Stacked and scaled to percentages of the total
+    Normalized,
+}
+
+struct DataLabel {
+    x: f32,
+    y: f32,
+    text: String,
+}
+
+struct LinePath {
+    d: String,
+    /// Closed path of the filled area
+    area: Option<String>,
+    stroke: String,
+}
@@ -0,0 +1,51 @@
+use graph::HTML;
+
+/// Shape drawn at a data point
+#[derive(Clone, Copy)]
+pub enum Marker {
+    Circle,
+    Square,
+    Triangle,
+    Cross,
+}
+
+impl Marker {
+    /// Marker centered at `(x, y)` that fits a box `size` pixels wide,
+    /// the cross is drawn with the stroke color if there is one
+    pub fn render(&self, x: f32, y: f32, size: f32, fill: &str,
+                  stroke: Option<&str>) -> HTML {
+        let r = size / 2.0;
+        let stroke_width = size / 5.0;
+        let outline = stroke.unwrap_or("none");
+
+        match *self {
+            Marker::Circle   => html! {
+                circle.marker cx=(x) cy=(y) r=(r) fill=(fill) stroke=(outline) stroke-width=(stroke_width) {}
+            },
+            Marker::Square   => html! {
+                rect.marker x=(x - r) y=(y - r) width=(size) height=(size) fill=(fill) stroke=(outline) stroke-width=(stroke_width) {}
+            },
+            Marker::Triangle => {
+                // Equilateral triangle with its centroid at the point
+                let h = size * 0.866;
+                let points = format!("{},{} {},{} {},{}",
+                                     x, y - h * 2.0 / 3.0,
+                                     x + r, y + h / 3.0,
+                                     x - r, y + h / 3.0);
+
+                html! {
+                    polygon.marker points=(points) fill=(fill) stroke=(outline) stroke-width=(stroke_width) {}
+                }
+            },
+            Marker::Cross    => {
+                let d = format!("M{},{}L{},{}M{},{}L{},{}",
+                                x - r, y - r, x + r, y + r,
+                                x - r, y + r, x + r, y - r);
+
+                html! {
+                    path.marker d=(d) stroke=(stroke.unwrap_or(fill)) stroke-width=(size / 4.0) fill="none" {}
+                }
+            },
+        }
+    }
+}
@@ -1,11 +1,36 @@
-use std::f32::consts;
+use std::cmp::Ordering;
 
 use graph::{Graph, Tools, Coord, Padding, HTML, Size};
 use entry::Entry;
+use title::Title;
+use theme::Theme;
+use legend::{Legend, LegendItem, Swatch};
+use text::TextMetrics;
+
+/// Distance of the leader line bend from the pie
+const ELBOW: f32 = 10.0;
+/// Distance of the leader line end from the pie
+const LEADER: f32 = 20.0;
 
 pub struct PieBuilder {
     width: f32,
     height: f32,
+    theme: Theme,
+    title: Option<String>,
+    subtitle: Option<String>,
+    legend: Option<Legend>,
+    inner_radius: f32,
+    center_text: Option<String>,
+    start_angle: f32,
+    direction: Direction,
+    pad_angle: f32,
+    corner_radius: f32,
+    slice_labels: PieLabels,
+    label_threshold: f32,
+    top: Option<usize>,
+    min_fraction: f32,
+    other_label: String,
+    text: SliceFormat,
     entries: Option<Vec<Entry>>,
 }
 
@@ -14,6 +39,22 @@ impl PieBuilder {
         PieBuilder {
             width: 500.0,
             height: 500.0,
+            theme: Theme::light(),
+            title: None,
+            subtitle: None,
+            legend: None,
+            inner_radius: 0.0,
+            center_text: None,
+            start_angle: 270.0,
+            direction: Direction::CounterClockwise,
+            pad_angle: 0.0,
+            corner_radius: 0.0,
+            slice_labels: PieLabels::Inside,
+            label_threshold: 0.0,
+            top: None,
+            min_fraction: 0.0,
+            other_label: "Other".to_string(),
+            text: SliceFormat { text: None, decimals: None },
             entries: None,
         }
     }
@@ -33,135 +74,439 @@ impl PieBuilder {
         self
     }
 
+    /// Legend with a box per entry, placed to the right by default
+    pub fn legend(mut self, legend: Legend) -> PieBuilder {
+        self.legend = Some(legend);
+        self
+    }
+
+    /// Draw a donut, the hole radius is a fraction of the outer radius
+    pub fn inner_radius(mut self, ratio: f32) -> PieBuilder {
+        self.inner_radius = ratio;
+        self
+    }
+
+    /// Text in the middle of a donut, e.g. the total
+    pub fn center_text<S>(mut self, text: S) -> PieBuilder where S: Into<String> {
+        self.center_text = Some(text.into());
+        self
+    }
+
+    /// Where the first slice starts in degrees clockwise from
+    /// 12 o'clock, 270 (9 o'clock) by default
+    pub fn start_angle(mut self, degrees: f32) -> PieBuilder {
+        self.start_angle = degrees;
+        self
+    }
+
+    /// Direction in which the slices follow each other,
+    /// counter-clockwise by default
+    pub fn direction(mut self, direction: Direction) -> PieBuilder {
+        self.direction = direction;
+        self
+    }
+
+    /// Gap between the slices in degrees
+    pub fn pad_angle(mut self, degrees: f32) -> PieBuilder {
+        self.pad_angle = degrees;
+        self
+    }
+
+    /// Radius of the rounded slice corners in pixels
+    pub fn corner_radius(mut self, radius: f32) -> PieBuilder {
+        self.corner_radius = radius;
+        self
+    }
+
+    /// Where the slice labels are drawn, inside the slices by default
+    pub fn slice_labels(mut self, labels: PieLabels) -> PieBuilder {
+        self.slice_labels = labels;
+        self
+    }
+
+    /// Slices below this fraction of the total are only named
+    /// in the legend, e.g. `0.03` for 3%
+    pub fn label_threshold(mut self, fraction: f32) -> PieBuilder {
+        self.label_threshold = fraction;
+        self
+    }
+
+    /// What the slice labels show, also used for the legend rows
+    /// which otherwise show only the entry label
+    pub fn slice_text(mut self, text: SliceText) -> PieBuilder {
+        self.text.text = Some(text);
+        self
+    }
+
+    /// Decimals of the values and percentages in the slice text
+    pub fn decimals(mut self, decimals: usize) -> PieBuilder {
+        self.text.decimals = Some(decimals);
+        self
+    }
+
+    /// Keep the `n` largest entries and merge the rest into one slice.
+    /// Entries are sorted by value when they are merged.
+    pub fn top(mut self, n: usize) -> PieBuilder {
+        self.top = Some(n);
+        self
+    }
+
+    /// Merge entries below this fraction of the total into one slice,
+    /// e.g. `0.02` for 2%
+    pub fn min_fraction(mut self, fraction: f32) -> PieBuilder {
+        self.min_fraction = fraction;
+        self
+    }
+
+    /// Label of the merged slice, "Other" by default
+    pub fn other_label<S>(mut self, label: S) -> PieBuilder where S: Into<String> {
+        self.other_label = label.into();
+        self
+    }
+
+    /// Colors and fonts, `Theme::light()` by default
+    pub fn theme(mut self, theme: Theme) -> PieBuilder {
+        self.theme = theme;
+        self
+    }
+
+    pub fn title<S>(mut self, title: S) -> PieBuilder where S: Into<String> {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn subtitle<S>(mut self, subtitle: S) -> PieBuilder where S: Into<String> {
+        self.subtitle = Some(subtitle.into());
+        self
+    }
+
     pub fn build(self) -> Pie {
         let padding = Padding::with_same(15.0);
-        let Padding { top, right, bottom, left } = padding;
+        let theme = self.theme;
         let (width, height) = (self.width, self.height);
+        // Missing values have no slice
         let entries = match self.entries {
-            Some(e) => e,
+            Some(e) => e.into_iter().filter(|e| e.value.is_some()).collect(),
             None    => Vec::with_capacity(0),
         };
-
-        let label_width = 100.0; // TODO: calculate dynamically
-        let label_padding_left = 20.0;
-        let label_padding_top = 30.0;
-        let body = Coord {
-            x: left,
-            y: top,
-            width: width - label_width - label_padding_left - left - right,
-            height: height - top - bottom,
+        let entries = if self.top.is_some() || self.min_fraction > 0.0 {
+            PieBuilder::merge_small(entries, self.top, self.min_fraction, self.other_label)
+        } else {
+            entries
         };
+        let sum = entries
+            .iter()
+            .fold(0.0, |acc, e| acc + e.value.unwrap_or(0.0));
 
-        let labels_body = Coord {
-            x: body.x + body.width + label_padding_left,
-            y: top + label_padding_top,
-            width: label_width,
-            height: body.height - label_padding_top,
-        };
+        let legend = match self.legend {
+            Some(l) => l,
+            None    => Legend::new(),
+        }.items(Pie::labels(&entries, &theme, &self.text, sum));
+        let content = Coord::from_padding(&padding, (0.0, 0.0, width, height));
+        let title = Title::new(self.title, self.subtitle);
+        let (content, title_body) = title.split(&content);
+        let (body, legend) = Legend::layout(Some(legend), content, &theme);
 
         Pie {
             size: Size { width: width, height: height },
+            title: (title, title_body),
+            theme: theme,
             body: body,
-            labels_body: labels_body,
-            sum: entries
-                .iter()
-                .fold(0, |acc, e| acc + e.value),
+            legend: legend,
+            inner_radius: self.inner_radius,
+            center_text: self.center_text,
+            start_angle: self.start_angle,
+            direction: self.direction,
+            pad_angle: self.pad_angle,
+            corner_radius: self.corner_radius,
+            slice_labels: self.slice_labels,
+            label_threshold: self.label_threshold,
+            text: self.text,
+            sum: sum,
             entries: entries,
         }
     }
+
+    /// Entries sorted from the largest, with the ones outside of the
+    /// top `n` or below `min_fraction` of the total merged into the last one
+    fn merge_small(mut entries: Vec<Entry>, top: Option<usize>, min_fraction: f32,
+                   other_label: String) -> Vec<Entry> {
+        let sum = entries.iter().fold(0.0, |acc, e| acc + e.value.unwrap_or(0.0));
+        entries.sort_by(|a, b| b.value.partial_cmp(&a.value).unwrap_or(Ordering::Equal));
+
+        let keep = entries
+            .iter()
+            .enumerate()
+            .take_while(|&(i, e)| {
+                top.map_or(true, |n| i < n) && e.value.unwrap_or(0.0) >= sum * min_fraction
+            })
+            .count();
+
+        // A single small entry is shown as itself
+        if entries.len() - keep < 2 {
+            return entries;
+        }
+
+        let rest = entries.split_off(keep);
+        let other = rest.iter().fold(0.0, |acc, e| acc + e.value.unwrap_or(0.0));
+        entries.push(Entry::new(other_label, other));
+        entries
+    }
 }
 
 pub struct Pie {
     size: Size,
+    title: (Title, Coord),
+    theme: Theme,
     entries: Vec<Entry>,
-    labels_body: Coord,
+    legend: Option<(Legend, Coord)>,
     body: Coord,
-    sum: i32,
+    inner_radius: f32,
+    center_text: Option<String>,
+    start_angle: f32,
+    direction: Direction,
+    pad_angle: f32,
+    corner_radius: f32,
+    slice_labels: PieLabels,
+    label_threshold: f32,
+    text: SliceFormat,
+    sum: f32,
 }
 
 impl Pie {
-    fn angle(&self, v: i32) -> f32 {
-        (v as f32) / (self.sum as f32) * 2.0 * consts::PI
-    }
-
-    fn color(i: usize) -> String {
-        let colors = vec![
-            //"rgba(0,0,0,0.5)",
-            //"rgba(0,94,255,0.5)",
-            //"rgba(255,165,0,0.5)",
-            //"rgba(10,199,0,0.5)",
-            //"rgba(220,232,0,0.5)",
-            //"rgba(232,0,162,0.5)"
-            //
-            "rgb(237,10,63)",
-            "rgb(231,114,0)",
-            "rgb(254,216,93)",
-            "rgb(1,120,111)",
-            "rgb(165,7,44)",
-            "rgb(243,184,127)",
-            "rgb(66,75,77)",
-            "rgb(203,172,74)",
-            "rgb(153,201,197)",
-            "rgb(129,135,136)",
-        ];
+    /// Angle of the value in degrees
+    fn angle(&self, v: f32) -> f32 {
+        v / self.sum * 360.0
+    }
 
-        if i >= colors.len() {
-            Pie::color(i - colors.len())
-        } else {
-            colors[i].to_string()
+    /// 1 when angles grow clockwise
+    fn sign(&self) -> f32 {
+        match self.direction {
+            Direction::Clockwise        => 1.0,
+            Direction::CounterClockwise => -1.0,
+        }
+    }
+
+    /// Outer radius, smaller when the labels are drawn around the pie
+    fn radius(&self) -> f32 {
+        let r = self.body.width.min(self.body.height) / 2.0;
+
+        match self.slice_labels {
+            PieLabels::Outside => {
+                let texts = self.entries
+                    .iter()
+                    .filter(|e| self.is_labeled(e))
+                    .map(|e| self.text.apply(e, self.sum))
+                    .collect::<Vec<String>>();
+                let text_w = TextMetrics::max_width(texts.iter(), self.theme.font_size);
+
+                (self.body.width / 2.0 - LEADER - 5.0 - text_w)
+                    .min(self.body.height / 2.0 - self.theme.font_size)
+                    .max(10.0)
+            },
+            _ => r,
         }
     }
 
+    fn is_labeled(&self, e: &Entry) -> bool {
+        self.sum > 0.0 && e.value.unwrap_or(0.0) / self.sum >= self.label_threshold
+    }
+
     fn arcs(&self) -> Vec<Arc> {
-        let r = if self.body.height > self.body.width {
-            self.body.width / 2.0
-        } else {
-            self.body.height / 2.0
-        };
-        let text_r = r * 0.85;
-        let mut prev_angle = 0.0;
+        let r = self.radius();
+        let inner = r * self.inner_radius;
+        let text_r = if inner > 0.0 { (r + inner) / 2.0 } else { r * 0.85 };
+        let pad = if self.entries.len() > 1 { self.pad_angle / 2.0 } else { 0.0 };
+        let mut prev_angle = self.start_angle;
+        let mut middles = Vec::new();
 
-        self.entries
+        let mut arcs = self.entries
             .iter()
             .enumerate()
             .map(|(i, e)| {
-                let d_angle = self.angle(e.value);
+                let value = e.value.unwrap_or(0.0);
+                let d_angle = self.angle(value) * self.sign();
                 let next_angle = prev_angle + d_angle;
-                let a = (-r * prev_angle.cos(), r * prev_angle.sin());
-                let b = (-r * next_angle.cos(), r * next_angle.sin());
+                let path = if d_angle.abs() > pad * 2.0 {
+                    self.slice_path(r, inner, prev_angle + pad * self.sign(),
+                                    next_angle - pad * self.sign())
+                } else {
+                    String::new()
+                };
 
                 let text_angle = prev_angle + (d_angle / 2.0);
-                let (text_x, text_y) = (
-                    -text_r * text_angle.cos(),
-                     text_r * text_angle.sin()
-                );
-
                 prev_angle = next_angle;
-
-                let text = format!("{} ({})", e.label, e.value);
+                if self.is_labeled(e) {
+                    middles.push((i, text_angle));
+                }
 
                 Arc {
-                    path: format!("M{},{}A{},{},0,0,0,{},{}L0,0Z", a.0, a.1, r, r, b.0, b.1),
-                    fill: Pie::color(i),
-                    text_dx: -(text.len() as f32 * 2.0),
-                    text: text,
-                    text_x: text_x,
-                    text_y: text_y,
+                    path: path,
+                    fill: self.theme.color(i),
+                    label: None,
                 }
             })
-            .collect()
+            .collect::<Vec<Arc>>();
+
+        let labels = match self.slice_labels {
+            PieLabels::Inside  => middles
+                .into_iter()
+                .map(|(i, angle)| {
+                    let (x, y) = Pie::point(text_r, angle);
+                    (i, SliceLabel {
+                        text: self.text.apply(&self.entries[i], self.sum),
+                        x: x,
+                        y: y,
+                        anchor: "middle",
+                        leader: None,
+                    })
+                })
+                .collect(),
+            PieLabels::Outside => self.outside_labels(r, middles),
+            PieLabels::Hidden  => Vec::new(),
+        };
+
+        for (i, label) in labels {
+            arcs[i].label = Some(label);
+        }
+
+        arcs
+    }
+
+    /// Labels to the left and right of the pie connected by leader lines,
+    /// moved apart vertically so they don't overlap
+    fn outside_labels(&self, r: f32, middles: Vec<(usize, f32)>) -> Vec<(usize, SliceLabel)> {
+        let spacing = self.theme.font_size + 2.0;
+        let bound = self.body.height / 2.0;
+        let mut labels = Vec::new();
+
+        for &side in [1.0, -1.0].iter() {
+            let mut slices = middles
+                .iter()
+                .map(|&(i, angle)| (i, angle, Pie::point(r + ELBOW, angle)))
+                .filter(|&(_, _, (x, _))| (x >= 0.0) == (side > 0.0))
+                .collect::<Vec<_>>();
+            slices.sort_by(|a, b| (a.2).1.partial_cmp(&(b.2).1).unwrap_or(Ordering::Equal));
+
+            let ys = Pie::spread(slices.iter().map(|s| (s.2).1).collect(), spacing, bound);
+
+            for (&(i, angle, (elbow_x, elbow_y)), y) in slices.iter().zip(ys.into_iter()) {
+                let (start_x, start_y) = Pie::point(r, angle);
+                let end_x = side * (r + LEADER);
+
+                labels.push((i, SliceLabel {
+                    text: self.text.apply(&self.entries[i], self.sum),
+                    x: end_x + side * 3.0,
+                    y: y + self.theme.font_size / 3.0,
+                    anchor: if side > 0.0 { "start" } else { "end" },
+                    leader: Some(format!("{},{} {},{} {},{}",
+                                         start_x, start_y, elbow_x, elbow_y, end_x, y)),
+                }));
+            }
+        }
+
+        labels
+    }
+
+    /// Push sorted positions apart to at least `spacing`, keeping
+    /// them inside `-bound..bound` where there is room
+    fn spread(mut ys: Vec<f32>, spacing: f32, bound: f32) -> Vec<f32> {
+        for i in 0..ys.len() {
+            let min = if i > 0 { ys[i - 1] + spacing } else { -bound };
+            ys[i] = ys[i].max(min);
+        }
+
+        for i in (0..ys.len()).rev() {
+            let max = if i + 1 < ys.len() { ys[i + 1] - spacing } else { bound };
+            ys[i] = ys[i].min(max);
+        }
+
+        ys
+    }
+
+    /// Outline of a slice from the `from` to the `to` angle. Rounded
+    /// corners are curves through the sharp corner they replace.
+    fn slice_path(&self, r: f32, inner: f32, from: f32, to: f32) -> String {
+        let sweep = match self.direction {
+            Direction::Clockwise        => 1,
+            Direction::CounterClockwise => 0,
+        };
+        let half = (to - from).abs() / 2.0;
+        let cr = self.corner_radius.min((r - inner) / 2.0).max(0.0);
+        // Angles taken by the corners along the outer and inner arcs
+        let outer_d = (cr / r).to_degrees().min(half) * self.sign();
+        let inner_d = if inner > 0.0 {
+            (cr / inner).to_degrees().min(half) * self.sign()
+        } else {
+            0.0
+        };
+
+        let mut path = Pie::point_command("M", r, from + outer_d);
+        path.push_str(&Pie::arc(r, from + outer_d, to - outer_d, sweep));
+        if cr > 0.0 {
+            path.push_str(&Pie::corner(r, to, r - cr, to));
+        }
+
+        if inner > 0.0 {
+            path.push_str(&Pie::point_command("L", inner + cr, to));
+            if cr > 0.0 {
+                path.push_str(&Pie::corner(inner, to, inner, to - inner_d));
+            }
+            path.push_str(&Pie::arc(inner, to - inner_d, from + inner_d, 1 - sweep));
+            if cr > 0.0 {
+                path.push_str(&Pie::corner(inner, from, inner + cr, from));
+            }
+        } else {
+            path.push_str("L0,0");
+        }
+
+        if cr > 0.0 {
+            path.push_str(&Pie::point_command("L", r - cr, from));
+            path.push_str(&Pie::corner(r, from, r, from + outer_d));
+        }
+
+        path.push_str("Z");
+        path
+    }
+
+    /// Point at `angle` degrees clockwise from 12 o'clock
+    fn point(r: f32, angle: f32) -> (f32, f32) {
+        let a = angle.to_radians();
+        (r * a.sin(), -r * a.cos())
+    }
+
+    fn point_command(command: &str, r: f32, angle: f32) -> String {
+        let (x, y) = Pie::point(r, angle);
+        format!("{}{},{}", command, x, y)
+    }
+
+    /// Arc drawn in two halves, which also works for a full circle
+    fn arc(r: f32, from: f32, to: f32, sweep: i32) -> String {
+        let (mx, my) = Pie::point(r, (from + to) / 2.0);
+        let (x, y) = Pie::point(r, to);
+        format!("A{},{},0,0,{},{},{}A{},{},0,0,{},{},{}", r, r, sweep, mx, my, r, r, sweep, x, y)
+    }
+
+    /// Curve from the current point to `(end_r, end_angle)` through the corner
+    fn corner(r: f32, angle: f32, end_r: f32, end_angle: f32) -> String {
+        let (cx, cy) = Pie::point(r, angle);
+        let (x, y) = Pie::point(end_r, end_angle);
+        format!("Q{},{},{},{}", cx, cy, x, y)
     }
 
-    fn labels(&self) -> Vec<Label> {
-        self.entries
+    fn labels(entries: &Vec<Entry>, theme: &Theme, text: &SliceFormat,
+              sum: f32) -> Vec<LegendItem> {
+        entries
             .iter()
             .enumerate()
             .map(|(i, e)| {
-                Label {
-                    fill: Pie::color(i),
-                    text: e.label.clone(),
-                    x: 0.0,
-                    y: (i as f32) * 20.0,
+                LegendItem {
+                    text: match text.text {
+                        Some(_) => text.apply(e, sum),
+                        None    => e.label.clone(),
+                    },
+                    color: theme.color(i),
+                    swatch: Swatch::Rect,
                 }
             })
             .collect()
@@ -171,28 +516,34 @@ impl Pie {
 impl Graph for Pie {
     fn into_html(&self) -> HTML {
         let center = self.body.center();
-        let labels = self.labels();
         let arcs = self.arcs();
 
         html! {
-            svg width=(self.size.width) height=(self.size.height) xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" {
+            svg width=(self.size.width) height=(self.size.height) xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" font-family=(self.theme.font_family) font-size=(self.theme.font_size) {
 
-                g.labels transform=(Tools::tr(self.labels_body.x, self.labels_body.y)) {
-                    @for Label { fill, text, x, y } in labels {
-                        g.label transform=(Tools::tr(x, y)) {
-                            rect fill=(fill) x="0" y="-11" width="12" height="12" {}
-                            text anchor="start" dx="18" (text)
-                        }
-                    }
+                (self.theme.background(&self.size))
+                (self.title.0.render(&self.title.1, &self.theme))
+
+                @if let Some((ref legend, ref legend_body)) = self.legend {
+                    (legend.render(legend_body, &self.theme))
                 }
 
                 g.content transform=(Tools::tr(center.0, center.1)) {
-                    @for Arc { path, fill, text, text_x, text_y, text_dx } in arcs {
+                    @for Arc { path, fill, label } in arcs {
                         g.arc {
-                            path stroke="rgba(245,245,245,0.8)" fill=(fill) d=(path) {}
-                            text transform=(Tools::tr(text_x, text_y)) dx=(text_dx) anchor="middle" (text)
+                            path stroke=(self.theme.slice_stroke) fill=(fill) d=(path) {}
+
+                            @if let Some(SliceLabel { text, x, y, anchor, leader }) = label {
+                                @if let Some(points) = leader {
+                                    polyline.leader points=(points) fill="none" stroke=(self.theme.text_color) {}
+                                }
+                                text x=(x) y=(y) text-anchor=(anchor) fill=(self.theme.text_color) (text)
+                            }
                         }
+                    }
 
+                    @if let Some(ref text) = self.center_text {
+                        text.center-text x="0" y=(self.theme.title_font_size / 3.0) text-anchor="middle" font-size=(self.theme.title_font_size) fill=(self.theme.text_color) (text)
                     }
                 }
             }
@@ -200,18 +551,131 @@ impl Graph for Pie {
     }
 }
 
+/// Order in which the slices are drawn
+pub enum Direction {
+    Clockwise,
+    CounterClockwise,
+}
+
+/// Content of the slice labels
+pub enum SliceText {
+    /// Label and value, e.g. `Chrome (640)`
+    Value,
+    /// Label and share of the total, e.g. `Chrome (64%)`
+    Percent,
+    /// e.g. `Chrome (640, 64%)`
+    Both,
+    /// Text from the entry and its share of the total between 0 and 1
+    Custom(Box<Fn(&Entry, f32) -> String>),
+}
+
+struct SliceFormat {
+    /// `None` shows the value in the slices and only the label in the legend
+    text: Option<SliceText>,
+    decimals: Option<usize>,
+}
+
+impl SliceFormat {
+    fn apply(&self, e: &Entry, sum: f32) -> String {
+        let value = e.value.unwrap_or(0.0);
+        let share = if sum > 0.0 { value / sum } else { 0.0 };
+        let number = |v: f32| match self.decimals {
+            Some(d) => format!("{:.*}", d, v),
+            None    => Tools::format_value(v),
+        };
+
+        match self.text {
+            None |
+            Some(SliceText::Value)       => format!("{} ({})", e.label, number(value)),
+            Some(SliceText::Percent)     => format!("{} ({}%)", e.label, number(share * 100.0)),
+            Some(SliceText::Both)        => {
+                format!("{} ({}, {}%)", e.label, number(value), number(share * 100.0))
+            },
+            Some(SliceText::Custom(ref f)) => f(e, share),
+        }
+    }
+}
+
+/// Placement of the slice labels
+pub enum PieLabels {
+    Inside,
+    /// Around the pie with leader lines
+    Outside,
+    /// Slices are only named in the legend
+    Hidden,
+}
+
 struct Arc {
     path: String,
     fill: String,
-    text: String,
-    text_x: f32,
-    text_y: f32,
-    text_dx: f32,
+    label: Option<SliceLabel>,
 }
 
-struct Label {
-    fill: String,
+struct SliceLabel {
     text: String,
     x: f32,
     y: f32,
+    anchor: &'static str,
+    /// Polyline points from the slice to the text
+    leader: Option<String>,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(entries: &Vec<Entry>) -> Vec<(String, Option<f32>)> {
+        entries.iter().map(|e| (e.label.clone(), e.value)).collect()
+    }
+
+    #[test]
+    fn merge_small() {
+        let entries = || vec![
+            Entry::new("a", 5),
+            Entry::new("b", 40),
+            Entry::new("c", 3),
+            Entry::new("d", 50),
+            Entry::new("e", 2),
+        ];
+
+        let merged = PieBuilder::merge_small(entries(), Some(2), 0.0, "Other".to_string());
+        assert_eq!(labels(&merged), vec![("d".to_string(), Some(50.0)),
+                                         ("b".to_string(), Some(40.0)),
+                                         ("Other".to_string(), Some(10.0))]);
+
+        let merged = PieBuilder::merge_small(entries(), None, 0.04, "Rest".to_string());
+        assert_eq!(labels(&merged), vec![("d".to_string(), Some(50.0)),
+                                         ("b".to_string(), Some(40.0)),
+                                         ("a".to_string(), Some(5.0)),
+                                         ("Rest".to_string(), Some(5.0))]);
+
+        // Nothing to merge with a single small entry
+        let merged = PieBuilder::merge_small(entries(), Some(4), 0.0, "Other".to_string());
+        assert_eq!(labels(&merged).len(), 5);
+    }
+
+    #[test]
+    fn slice_text() {
+        let e = Entry::new("a", 25);
+        let format = |text, decimals| SliceFormat { text: text, decimals: decimals };
+
+        assert_eq!(format(None, None).apply(&e, 200.0), "a (25)");
+        assert_eq!(format(Some(SliceText::Percent), None).apply(&e, 200.0), "a (12.5%)");
+        assert_eq!(format(Some(SliceText::Both), Some(1)).apply(&e, 200.0), "a (25.0, 12.5%)");
+        assert_eq!(format(Some(SliceText::Value), Some(2)).apply(&e, 0.0), "a (25.00)");
+
+        let custom = SliceText::Custom(Box::new(|e, share| {
+            format!("{}: {:.0}", e.label, share * 100.0)
+        }));
+        assert_eq!(format(Some(custom), None).apply(&e, 100.0), "a: 25");
+    }
+
+    #[test]
+    fn spread() {
+        assert_eq!(Pie::spread(vec![0.0, 5.0, 30.0], 10.0, 100.0), vec![0.0, 10.0, 30.0]);
+        assert_eq!(Pie::spread(vec![90.0, 95.0, 99.0], 10.0, 100.0), vec![80.0, 90.0, 100.0]);
+        assert_eq!(Pie::spread(vec![-100.0, -100.0], 10.0, 100.0), vec![-100.0, -90.0]);
+        assert_eq!(Pie::spread(vec![], 10.0, 100.0), vec![]);
+    }
 }
@@ -0,0 +1,39 @@
+/// Data point with numeric coordinates, used by scatter graphs.
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+    /// Marker size in pixels, overrides the graph marker size
+    pub size: Option<f32>,
+    /// Third value, encoded as the bubble area in bubble graphs
+    pub value: Option<f32>,
+    pub color: Option<String>,
+}
+
+impl Point {
+    /// Accepts both integer and floating-point values,
+    /// e.g. `Point::new(3, 12.7)`.
+    pub fn new<X, Y>(x: X, y: Y) -> Point where X: Into<f64>, Y: Into<f64> {
+        Point {
+            x: x.into() as f32,
+            y: y.into() as f32,
+            size: None,
+            value: None,
+            color: None,
+        }
+    }
+
+    pub fn size(mut self, size: f32) -> Point {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn value<V>(mut self, value: V) -> Point where V: Into<f64> {
+        self.value = Some(value.into() as f32);
+        self
+    }
+
+    pub fn color<S>(mut self, color: S) -> Point where S: Into<String> {
+        self.color = Some(color.into());
+        self
+    }
+}
@@ -1,8 +1,156 @@
+use graph::Tools;
+
 pub trait Scale {
     fn offset(&self, i: f32) -> f32;
     fn segment(&self) -> f32;
-    fn ticks(&self) -> Vec<i32>;
+    fn ticks(&self) -> Vec<f32>;
     fn with_range(&self, start: f32, stop: f32) -> Box<Scale>;
+
+    /// Copy of the scale with ticks at least `spacing` pixels apart.
+    /// Scales with fixed ticks return the same ticks.
+    fn with_tick_spacing(&self, spacing: f32) -> Box<Scale>;
+
+    /// Unlabeled ticks between the main ones
+    fn minor_ticks(&self) -> Vec<f32> {
+        Vec::new()
+    }
+
+    /// Value bars grow from
+    fn baseline(&self) -> f32 {
+        0.0
+    }
+
+    /// Text shown next to the tick
+    fn tick_label(&self, v: f32) -> String {
+        Tools::format_value(v)
+    }
+}
+
+/// Scale used for the value axis
+pub enum ScaleKind {
+    Linear,
+    /// Logarithmic scale with the given base
+    Log(f32),
+}
+
+/// Value scale of an axis with its tick options,
+/// a plain `ScaleKind` converts into the default options
+pub struct ScaleOptions {
+    kind: ScaleKind,
+    tick_count: Option<usize>,
+    nice: bool,
+    min: Option<f32>,
+    max: Option<f32>,
+    include_zero: bool,
+    padding: f32,
+    reversed: bool,
+}
+
+impl ScaleOptions {
+    pub fn new(kind: ScaleKind) -> ScaleOptions {
+        ScaleOptions {
+            kind: kind,
+            tick_count: None,
+            nice: true,
+            min: None,
+            max: None,
+            include_zero: true,
+            padding: 0.0,
+            reversed: false,
+        }
+    }
+
+    /// Approximate number of ticks of a linear scale
+    pub fn tick_count(mut self, count: usize) -> ScaleOptions {
+        self.tick_count = Some(count);
+        self
+    }
+
+    /// Extend a linear scale to the surrounding ticks (default),
+    /// or end it at the data
+    pub fn nice(mut self, nice: bool) -> ScaleOptions {
+        self.nice = nice;
+        self
+    }
+
+    /// Start of the domain instead of the lowest value
+    pub fn min(mut self, min: f32) -> ScaleOptions {
+        self.min = Some(min);
+        self
+    }
+
+    /// End of the domain instead of the highest value
+    pub fn max(mut self, max: f32) -> ScaleOptions {
+        self.max = Some(max);
+        self
+    }
+
+    /// Extend a linear domain to zero, on by default
+    pub fn include_zero(mut self, include_zero: bool) -> ScaleOptions {
+        self.include_zero = include_zero;
+        self
+    }
+
+    /// Widen a linear domain on both sides by a fraction
+    /// of its size, e.g. `0.05` for 5%
+    pub fn padding(mut self, padding: f32) -> ScaleOptions {
+        self.padding = padding;
+        self
+    }
+
+    /// Put the highest value at the start of the axis,
+    /// e.g. for rankings with 1 on top
+    pub fn reversed(mut self) -> ScaleOptions {
+        self.reversed = true;
+        self
+    }
+
+    /// Scale for values between `min` and `max`, `min` greater than
+    /// `max` means there are no values. Explicit ends are kept exactly
+    /// even if the scale is nice.
+    pub fn scale(&self, min: f32, max: f32) -> Box<Scale> {
+        let empty = min > max;
+        let scale: Box<Scale> = match self.kind {
+            ScaleKind::Linear    => {
+                let (min, max) = if empty { (0.0, 1.0) } else { (min, max) };
+                let d = (max - min) * self.padding;
+                let (min, max) = (min - d, max + d);
+                let (min, max) = if self.include_zero {
+                    (min.min(0.0), max.max(0.0))
+                } else {
+                    (min, max)
+                };
+                let scale = LinearRoundedScale::new(self.min.unwrap_or(min),
+                                                    self.max.unwrap_or(max))
+                    .nice(self.nice)
+                    .exact(self.min.is_some(), self.max.is_some());
+
+                Box::new(match self.tick_count {
+                    Some(n) => scale.tick_count(n),
+                    None    => scale,
+                })
+            },
+            ScaleKind::Log(base) => {
+                let (min, max) = if empty { (1.0, base) } else { (min, max) };
+
+                Box::new(
+                    LogScale::new(self.min.unwrap_or(min), self.max.unwrap_or(max)).base(base)
+                )
+            },
+        };
+
+        if self.reversed {
+            Box::new(ReversedScale::new(scale))
+        } else {
+            scale
+        }
+    }
+}
+
+impl From<ScaleKind> for ScaleOptions {
+    fn from(kind: ScaleKind) -> ScaleOptions {
+        ScaleOptions::new(kind)
+    }
 }
 
 pub struct LinearScale {
@@ -30,18 +178,34 @@ impl Scale for LinearScale {
         (self.range.1 - self.range.0) / (self.domain.1 - self.domain.0)
     }
 
-    fn ticks(&self) -> Vec<i32> {
-        (0..((self.domain.1 - self.domain.0 + 1.0) as i32)).collect()
+    fn ticks(&self) -> Vec<f32> {
+        (0..((self.domain.1 - self.domain.0 + 1.0) as i32))
+            .map(|t| t as f32)
+            .collect()
     }
 
     fn with_range(&self, start: f32, stop: f32) -> Box<Scale> {
         Box::new(LinearScale { range: (start, stop), .. *self })
     }
+
+    fn with_tick_spacing(&self, _spacing: f32) -> Box<Scale> {
+        Box::new(LinearScale { .. *self })
+    }
 }
 
+/// Default minimum distance between the ticks of a linear scale in pixels
+pub const TICK_SPACING: f32 = 40.0;
+
+/// Linear scale with ticks at 1, 2 or 5 times a power of ten
 pub struct LinearRoundedScale {
     domain: (f32, f32),
     range: (f32, f32),
+    tick_count: Option<usize>,
+    /// Minimum distance between the ticks when there is no tick count
+    spacing: f32,
+    nice: bool,
+    /// Ends of the domain that aren't extended to a tick
+    exact: (bool, bool),
 }
 
 impl LinearRoundedScale {
@@ -49,14 +213,74 @@ impl LinearRoundedScale {
         LinearRoundedScale {
             domain: (start, stop),
             range: (0.0, 0.0),
+            tick_count: None,
+            spacing: TICK_SPACING,
+            nice: true,
+            exact: (false, false),
+        }
+    }
+
+    /// Approximate number of ticks, one per `TICK_SPACING` by default
+    pub fn tick_count(mut self, count: usize) -> LinearRoundedScale {
+        self.tick_count = Some(count);
+        self
+    }
+
+    /// Extend the domain to the surrounding ticks (default), or keep
+    /// the exact ends
+    pub fn nice(mut self, nice: bool) -> LinearRoundedScale {
+        self.nice = nice;
+        self
+    }
+
+    /// Keep the start or the stop of the domain when the scale is nice
+    pub fn exact(mut self, start: bool, stop: bool) -> LinearRoundedScale {
+        self.exact = (start, stop);
+        self
+    }
+
+    /// Domain with some width even if all values are the same
+    fn span(&self) -> (f32, f32) {
+        let (start, stop) = self.domain;
+
+        if stop > start {
+            (start, stop)
+        } else if start == 0.0 {
+            (0.0, 1.0)
+        } else {
+            let d = start.abs() * 0.1;
+            (start - d, start + d)
         }
     }
 
     fn begin_end(&self) -> (f32, f32) {
-        let segment = self.segment();
-        let begin = Bound::Begin.bound(self.domain.0, segment);
-        let end = Bound::End.bound(self.domain.1, segment);
-        (begin, end)
+        let (start, stop) = self.span();
+
+        if self.nice {
+            let step = self.segment();
+            // Tolerance keeps values sitting on a tick from adding a step
+            let begin = if self.exact.0 { start } else { (start / step + 1e-4).floor() * step };
+            let end = if self.exact.1 { stop } else { (stop / step - 1e-4).ceil() * step };
+            (begin, end)
+        } else {
+            (start, stop)
+        }
+    }
+
+    /// Smallest 1, 2 or 5 times a power of ten not below `v`
+    fn nice_step(v: f32) -> f32 {
+        if !(v > 0.0) {
+            return 1.0;
+        }
+
+        let magnitude = 10.0f32.powf(v.log10().floor());
+        let step = match v / magnitude {
+            r @ _ if r <= 1.0 + 1e-4 => 1.0,
+            r @ _ if r <= 2.0 + 1e-4 => 2.0,
+            r @ _ if r <= 5.0 + 1e-4 => 5.0,
+            _                        => 10.0,
+        };
+        step * magnitude
     }
 }
 
@@ -65,76 +289,273 @@ impl Scale for LinearRoundedScale {
         let (begin, end) = self.begin_end();
         let d = i - begin;
         let ratio = d / (end - begin);
-        let res = self.range.0 + ratio * (self.range.1 - self.range.0);
-        res
+        self.range.0 + ratio * (self.range.1 - self.range.0)
     }
 
+    /// Distance between the ticks in domain units
     fn segment(&self) -> f32 {
-        // 1 tick per 25px
-        let k = 25.0;
-        let n = self.domain.1 - self.domain.0;
-        let h = self.range.1 - self.range.0;
-        let segment = {
-            let ticks = h / k;
-            let precise = n / ticks;
-            match precise {
-                p @ _ if p <= 1.0 => 1.0,
-                p @ _ if p <= 2.0 => 2.0,
-                p @ _             => (p / 5.0).ceil() * 5.0,
-            }
+        let (start, stop) = self.span();
+        let count = match self.tick_count {
+            Some(n) => n,
+            None    => ((self.range.1 - self.range.0).abs() / self.spacing) as usize,
         };
-        segment
+
+        LinearRoundedScale::nice_step((stop - start) / count.max(2) as f32)
     }
 
-    fn ticks(&self) -> Vec<i32> {
-        let segment = self.segment();
+    fn ticks(&self) -> Vec<f32> {
+        let step = self.segment();
         let (begin, end) = self.begin_end();
-        let ticks = (end - begin) as f32 / (segment as f32) + 2.0;
+        let first = (begin / step - 1e-4).ceil() as i32;
+        let last = (end / step + 1e-4).floor() as i32;
 
-        (1..(ticks.ceil() as i32))
-            .map(|t| ((t - 1) as f32 * segment + begin) as i32)
+        (first..(last + 1))
+            .map(|k| k as f32 * step)
             .collect()
     }
 
+    /// Zero, or the nearest end of the domain if it doesn't include zero
+    fn baseline(&self) -> f32 {
+        let (begin, end) = self.begin_end();
+        begin.max(0.0f32.min(end))
+    }
+
     fn with_range(&self, start: f32, stop: f32) -> Box<Scale> {
         Box::new(LinearRoundedScale { range: (start, stop), .. *self })
     }
+
+    /// An explicit tick count is kept
+    fn with_tick_spacing(&self, spacing: f32) -> Box<Scale> {
+        Box::new(LinearRoundedScale { spacing: spacing.max(TICK_SPACING), .. *self })
+    }
 }
 
-#[derive(Debug)]
-enum Bound {
-    Begin,
-    End
+/// Scale running from the end of the range to the start
+pub struct ReversedScale {
+    scale: Box<Scale>,
+    range: (f32, f32),
 }
 
-impl Bound {
-    fn sign(self) -> f32 {
-        match self {
-            Bound::Begin => -1.0,
-            Bound::End   => 1.0,
+impl ReversedScale {
+    pub fn new(scale: Box<Scale>) -> ReversedScale {
+        ReversedScale {
+            scale: scale,
+            range: (0.0, 0.0),
         }
     }
+}
+
+impl Scale for ReversedScale {
+    fn offset(&self, i: f32) -> f32 {
+        self.range.0 + self.range.1 - self.scale.offset(i)
+    }
+
+    fn segment(&self) -> f32 {
+        self.scale.segment()
+    }
+
+    fn ticks(&self) -> Vec<f32> {
+        self.scale.ticks()
+    }
+
+    fn minor_ticks(&self) -> Vec<f32> {
+        self.scale.minor_ticks()
+    }
+
+    fn baseline(&self) -> f32 {
+        self.scale.baseline()
+    }
+
+    fn tick_label(&self, v: f32) -> String {
+        self.scale.tick_label(v)
+    }
+
+    fn with_range(&self, start: f32, stop: f32) -> Box<Scale> {
+        Box::new(ReversedScale {
+            scale: self.scale.with_range(start, stop),
+            range: (start, stop),
+        })
+    }
 
-    fn bound(self, v: f32, segment: f32) -> f32 {
-        if v == 0.0 {
-            v
-        } else if v % segment == 0.0 {
-            v + segment * self.sign()
+    fn with_tick_spacing(&self, spacing: f32) -> Box<Scale> {
+        Box::new(ReversedScale {
+            scale: self.scale.with_tick_spacing(spacing),
+            range: self.range,
+        })
+    }
+}
+
+pub struct LogScale {
+    base: f32,
+    domain: (f32, f32),
+    range: (f32, f32),
+}
+
+impl LogScale {
+    /// Non-positive `start` falls back to 1 (or to the power of the base
+    /// below `stop` if `stop` is less than 1).
+    pub fn new(start: f32, stop: f32) -> LogScale {
+        LogScale {
+            base: 10.0,
+            domain: (start, stop),
+            range: (0.0, 0.0),
+        }
+    }
+
+    pub fn base(mut self, base: f32) -> LogScale {
+        self.base = base;
+        self
+    }
+
+    fn log(&self, v: f32) -> f32 {
+        v.log(self.base)
+    }
+
+    /// Exponents of the powers of the base surrounding the domain
+    fn begin_end(&self) -> (i32, i32) {
+        let stop = if self.domain.1 > 0.0 { self.domain.1 } else { 1.0 };
+        let start = if self.domain.0 > 0.0 {
+            self.domain.0
+        } else {
+            1.0f32.min(self.base.powi((self.log(stop) + 1e-4).floor() as i32))
+        };
+
+        // Tolerance protects against log(1000) = 2.9999998
+        let begin = (self.log(start) + 1e-4).floor() as i32;
+        let end = (self.log(stop) - 1e-4).ceil() as i32;
+        if end > begin {
+            (begin, end)
         } else {
-            let k = v / segment;
-            let rounded = match self {
-                Bound::Begin => k.floor(),
-                Bound::End   => k.ceil(),
-            };
-            rounded * segment
+            (begin, begin + 1)
+        }
+    }
+}
+
+impl Scale for LogScale {
+    fn offset(&self, i: f32) -> f32 {
+        let (begin, end) = self.begin_end();
+        if i <= 0.0 {
+            return self.range.0;
+        }
+
+        let ratio = (self.log(i) - begin as f32) / ((end - begin) as f32);
+        self.range.0 + ratio * (self.range.1 - self.range.0)
+    }
+
+    fn segment(&self) -> f32 {
+        let (begin, end) = self.begin_end();
+        (self.range.1 - self.range.0) / ((end - begin) as f32)
+    }
+
+    fn ticks(&self) -> Vec<f32> {
+        let (begin, end) = self.begin_end();
+
+        (begin..(end + 1))
+            .map(|k| self.base.powi(k))
+            .collect()
+    }
+
+    fn minor_ticks(&self) -> Vec<f32> {
+        let (begin, end) = self.begin_end();
+        let multiples = self.base.ceil() as i32;
+
+        (begin..end)
+            .flat_map(|k| {
+                let power = self.base.powi(k);
+                (2..multiples).map(move |m| m as f32 * power)
+            })
+            .collect()
+    }
+
+    fn baseline(&self) -> f32 {
+        self.base.powi(self.begin_end().0)
+    }
+
+    fn with_range(&self, start: f32, stop: f32) -> Box<Scale> {
+        Box::new(LogScale { range: (start, stop), .. *self })
+    }
+
+    fn with_tick_spacing(&self, _spacing: f32) -> Box<Scale> {
+        Box::new(LogScale { .. *self })
+    }
+}
+
+/// Maps values to lengths so that the area of a square or circle
+/// with that side or diameter is proportional to the value
+pub struct SqrtScale {
+    domain: (f32, f32),
+    range: (f32, f32),
+}
+
+impl SqrtScale {
+    pub fn new(start: f32, stop: f32) -> SqrtScale {
+        SqrtScale {
+            domain: (start.max(0.0), stop.max(0.0)),
+            range: (0.0, 0.0),
         }
     }
+
+    /// Largest 1, 2 or 5 times a power of ten not above `v`
+    fn nice_floor(v: f32) -> f32 {
+        let magnitude = 10.0f32.powf((v.log10() + 1e-4).floor());
+        let step = match v / magnitude {
+            r @ _ if r >= 5.0 - 1e-4 => 5.0,
+            r @ _ if r >= 2.0 - 1e-4 => 2.0,
+            _                        => 1.0,
+        };
+        step * magnitude
+    }
+}
+
+impl Scale for SqrtScale {
+    fn offset(&self, i: f32) -> f32 {
+        let (start, stop) = (self.domain.0.sqrt(), self.domain.1.sqrt());
+        if stop <= start {
+            return self.range.1;
+        }
+
+        let ratio = (i.max(0.0).sqrt() - start) / (stop - start);
+        self.range.0 + ratio * (self.range.1 - self.range.0)
+    }
+
+    fn segment(&self) -> f32 {
+        (self.range.1 - self.range.0) / (self.domain.1.sqrt() - self.domain.0.sqrt())
+    }
+
+    /// Reference values at a tenth, a half and the whole domain,
+    /// rounded down to nice numbers
+    fn ticks(&self) -> Vec<f32> {
+        let mut ticks: Vec<f32> = Vec::new();
+
+        for f in [0.1, 0.5, 1.0].iter() {
+            let v = self.domain.1 * f;
+            if v <= self.domain.0 || v <= 0.0 {
+                continue;
+            }
+
+            let tick = SqrtScale::nice_floor(v);
+            if tick > self.domain.0 && ticks.last().map_or(true, |&t| t < tick) {
+                ticks.push(tick);
+            }
+        }
+
+        ticks
+    }
+
+    fn with_range(&self, start: f32, stop: f32) -> Box<Scale> {
+        Box::new(SqrtScale { range: (start, stop), .. *self })
+    }
+
+    fn with_tick_spacing(&self, _spacing: f32) -> Box<Scale> {
+        Box::new(SqrtScale { .. *self })
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::f32::{MAX, MIN};
 
     #[test]
     fn linear_scale() {
@@ -143,7 +564,7 @@ mod tests {
             assert_eq!(scale.segment(), 10.0);
             assert_eq!(scale.offset(0.0), 0.0);
             assert_eq!(scale.offset(5.0), 50.0);
-            assert_eq!(scale.ticks(), vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+            assert_eq!(scale.ticks(), vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0]);
         }
 
         {
@@ -151,7 +572,7 @@ mod tests {
             assert_eq!(scale.segment(), 10.0);
             assert_eq!(scale.offset(-5.0), 0.0);
             assert_eq!(scale.offset(5.0), 100.0);
-            assert_eq!(scale.ticks(), vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+            assert_eq!(scale.ticks(), vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0]);
         }
     }
 
@@ -161,34 +582,203 @@ mod tests {
             let scale = LinearRoundedScale::new(0.0, 10.0).with_range(0.0, 100.0);
             assert_eq!(scale.segment(), 5.0);
             assert_eq!(scale.offset(0.0), 0.0);
-            assert_eq!(scale.offset(5.0), 33.333336);
-            assert_eq!(scale.ticks(), vec![0, 5, 10, 15]);
+            assert_eq!(scale.offset(5.0), 50.0);
+            assert_eq!(scale.ticks(), vec![0.0, 5.0, 10.0]);
         }
 
         {
             let scale = LinearRoundedScale::new(-5.0, 5.0).with_range(0.0, 100.0);
             assert_eq!(scale.segment(), 5.0);
-            assert_eq!(scale.offset(-5.0), 25.0);
-            assert_eq!(scale.offset(5.0), 75.0);
-            assert_eq!(scale.ticks(), vec![-10, -5, 0, 5, 10]);
+            assert_eq!(scale.offset(-5.0), 0.0);
+            assert_eq!(scale.offset(5.0), 100.0);
+            assert_eq!(scale.ticks(), vec![-5.0, 0.0, 5.0]);
+        }
+
+        {
+            let scale = LinearRoundedScale::new(0.0, 1.0).with_range(0.0, 100.0);
+            assert_eq!(scale.segment(), 0.5);
+            assert_eq!(scale.ticks(), vec![0.0, 0.5, 1.0]);
+        }
+
+        {
+            // Ends are extended to the surrounding ticks
+            let scale = LinearRoundedScale::new(3.0, 97.0).tick_count(5).with_range(0.0, 100.0);
+            assert_eq!(scale.segment(), 20.0);
+            assert_eq!(scale.offset(0.0), 0.0);
+            assert_eq!(scale.offset(100.0), 100.0);
+            assert_eq!(scale.ticks(), vec![0.0, 20.0, 40.0, 60.0, 80.0, 100.0]);
+        }
+
+        {
+            // Wider spacing gives fewer ticks, a tick count is kept
+            let scale = LinearRoundedScale::new(0.0, 1000.0).with_range(0.0, 400.0);
+            assert_eq!(scale.ticks().len(), 11);
+            let scale = scale.with_tick_spacing(70.0);
+            assert_eq!(scale.ticks(), vec![0.0, 200.0, 400.0, 600.0, 800.0, 1000.0]);
+
+            let scale = LinearRoundedScale::new(0.0, 1000.0).tick_count(10)
+                .with_range(0.0, 400.0)
+                .with_tick_spacing(70.0);
+            assert_eq!(scale.ticks().len(), 11);
+        }
+
+        {
+            let scale = LinearRoundedScale::new(3.0, 97.0).tick_count(5).nice(false)
+                .with_range(0.0, 94.0);
+            assert_eq!(scale.offset(3.0), 0.0);
+            assert_eq!(scale.offset(97.0), 94.0);
+            assert_eq!(scale.ticks(), vec![20.0, 40.0, 60.0, 80.0]);
+        }
+
+        {
+            let scale = LinearRoundedScale::new(0.0012, 0.0048).tick_count(4).with_range(0.0, 100.0);
+            let ticks = scale.ticks().iter().map(|v| Tools::format_value(*v)).collect::<Vec<_>>();
+            assert_eq!(ticks, vec!["0.001", "0.002", "0.003", "0.004", "0.005"]);
+        }
+
+        {
+            let scale = LinearRoundedScale::new(7.0, 7.0).with_range(0.0, 100.0);
+            assert_eq!(scale.ticks().len(), 3);
+            let scale = LinearRoundedScale::new(0.0, 0.0).with_range(0.0, 100.0);
+            assert_eq!(scale.ticks(), vec![0.0, 0.5, 1.0]);
         }
     }
 
     #[test]
-    fn bound() {
-        assert_eq!(Bound::Begin.bound(0.0, 20.0), 0.0);
-        assert_eq!(Bound::Begin.bound(-19.0, 20.0), -20.0);
-        assert_eq!(Bound::Begin.bound(-21.0, 10.0), -30.0);
-        assert_eq!(Bound::Begin.bound(150.3, 5.0), 150.0);
-        assert_eq!(Bound::Begin.bound(3.0, 2.0), 2.0);
-        assert_eq!(Bound::Begin.bound(10.0, 10.0), 0.0);
-
-        assert_eq!(Bound::End.bound(10.0, 5.0), 15.0);
-        assert_eq!(Bound::End.bound(-19.0, 20.0), 0.0);
-        assert_eq!(Bound::End.bound(152.7, 5.0), 155.0);
-        assert_eq!(Bound::End.bound(3.0, 2.0), 4.0);
-
-        assert_eq!(Bound::Begin.sign(), -1.0);
-        assert_eq!(Bound::End.sign(), 1.0);
+    fn nice_step() {
+        assert_eq!(LinearRoundedScale::nice_step(0.7), 1.0);
+        assert_eq!(LinearRoundedScale::nice_step(1.0), 1.0);
+        assert_eq!(LinearRoundedScale::nice_step(1.3), 2.0);
+        assert_eq!(LinearRoundedScale::nice_step(3.0), 5.0);
+        assert_eq!(LinearRoundedScale::nice_step(70.0), 100.0);
+        assert_eq!(LinearRoundedScale::nice_step(0.0), 1.0);
+    }
+
+    #[test]
+    fn scale_options() {
+        {
+            let scale = ScaleOptions::new(ScaleKind::Linear)
+                .scale(9500.0, 9800.0)
+                .with_range(0.0, 100.0);
+            assert_eq!(scale.ticks().first(), Some(&0.0));
+        }
+
+        {
+            let scale = ScaleOptions::new(ScaleKind::Linear)
+                .include_zero(false)
+                .scale(9500.0, 9800.0)
+                .with_range(0.0, 100.0);
+            assert_eq!(scale.ticks(), vec![9400.0, 9600.0, 9800.0]);
+            assert_eq!(scale.baseline(), 9400.0);
+        }
+
+        {
+            let scale = ScaleOptions::new(ScaleKind::Linear)
+                .include_zero(false)
+                .padding(0.5)
+                .nice(false)
+                .scale(10.0, 20.0)
+                .with_range(0.0, 100.0);
+            assert_eq!(scale.offset(5.0), 0.0);
+            assert_eq!(scale.offset(25.0), 100.0);
+        }
+
+        {
+            let scale = ScaleOptions::new(ScaleKind::Linear)
+                .min(-50.0)
+                .max(50.0)
+                .scale(0.0, 10.0)
+                .with_range(0.0, 100.0);
+            assert_eq!(scale.offset(0.0), 50.0);
+        }
+
+        {
+            // Explicit ends are kept, the other one is still nice
+            let scale = ScaleOptions::new(ScaleKind::Linear)
+                .min(3.0)
+                .max(97.0)
+                .scale(10.0, 90.0)
+                .with_range(0.0, 94.0);
+            assert_eq!(scale.offset(3.0), 0.0);
+            assert_eq!(scale.offset(97.0), 94.0);
+            assert!(scale.ticks().iter().all(|t| *t >= 3.0 && *t <= 97.0));
+
+            let scale = ScaleOptions::new(ScaleKind::Linear)
+                .min(3.0)
+                .scale(10.0, 97.0)
+                .with_range(0.0, 97.0);
+            assert_eq!(scale.offset(3.0), 0.0);
+            assert_eq!(scale.offset(100.0), 97.0);
+        }
+
+        {
+            // No values
+            let scale = ScaleOptions::new(ScaleKind::Linear)
+                .include_zero(false)
+                .scale(MAX, MIN)
+                .with_range(0.0, 100.0);
+            assert_eq!(scale.offset(0.0), 0.0);
+            assert_eq!(scale.offset(1.0), 100.0);
+
+            let scale = ScaleOptions::new(ScaleKind::Log(10.0))
+                .scale(MAX, MIN)
+                .with_range(0.0, 100.0);
+            assert_eq!(scale.offset(1.0), 0.0);
+            assert_eq!(scale.offset(10.0), 100.0);
+        }
+
+        {
+            let scale = ScaleOptions::new(ScaleKind::Linear)
+                .min(1.0)
+                .max(10.0)
+                .nice(false)
+                .reversed()
+                .scale(1.0, 10.0)
+                .with_range(0.0, 90.0);
+            assert_eq!(scale.offset(1.0), 90.0);
+            assert_eq!(scale.offset(10.0), 0.0);
+        }
+    }
+
+    #[test]
+    fn log_scale() {
+        {
+            let scale = LogScale::new(1.0, 1000.0).with_range(0.0, 300.0);
+            assert_eq!(scale.segment(), 100.0);
+            assert_eq!(scale.offset(1.0), 0.0);
+            assert_eq!(scale.offset(100.0), 200.0);
+            assert_eq!(scale.ticks(), vec![1.0, 10.0, 100.0, 1000.0]);
+            assert_eq!(scale.minor_ticks().len(), 3 * 8);
+            assert_eq!(scale.baseline(), 1.0);
+        }
+
+        {
+            let scale = LogScale::new(0.0, 50.0).base(2.0).with_range(0.0, 100.0);
+            assert_eq!(scale.ticks(), vec![1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0]);
+            assert_eq!(scale.minor_ticks(), vec![]);
+            assert_eq!(scale.offset(-3.0), 0.0);
+        }
+    }
+
+    #[test]
+    fn sqrt_scale() {
+        {
+            let scale = SqrtScale::new(0.0, 100.0).with_range(0.0, 50.0);
+            assert_eq!(scale.offset(0.0), 0.0);
+            assert_eq!(scale.offset(25.0), 25.0);
+            assert_eq!(scale.offset(100.0), 50.0);
+            assert_eq!(scale.offset(-4.0), 0.0);
+            assert_eq!(scale.ticks(), vec![10.0, 50.0, 100.0]);
+        }
+
+        {
+            let scale = SqrtScale::new(0.0, 870.0).with_range(0.0, 50.0);
+            assert_eq!(scale.ticks(), vec![50.0, 200.0, 500.0]);
+        }
+
+        {
+            let scale = SqrtScale::new(0.0, 0.0).with_range(0.0, 50.0);
+            assert_eq!(scale.ticks(), vec![]);
+        }
     }
 }
@@ -0,0 +1,274 @@
+use std::cmp::Ordering;
+use std::f32::{MAX, MIN};
+
+use graph::{Graph, Tools, Coord, Padding, HTML, Size};
+use point::Point;
+use marker::Marker;
+use title::Title;
+use theme::Theme;
+use legend::SizeLegend;
+use axis::{Axes2d, LabelPosition, AxisOption, AxisKind};
+use scale::{Scale, ScaleKind, ScaleOptions, SqrtScale};
+
+pub struct ScatterBuilder {
+    width: f32,
+    height: f32,
+    theme: Theme,
+    title: Option<String>,
+    subtitle: Option<String>,
+    x_title: Option<String>,
+    y_title: Option<String>,
+    x_scale: ScaleOptions,
+    y_scale: ScaleOptions,
+    marker: Marker,
+    marker_size: f32,
+    bubble_size: Option<f32>,
+    points: Vec<Point>,
+}
+
+impl ScatterBuilder {
+    pub fn new() -> ScatterBuilder {
+        ScatterBuilder {
+            width: 500.0,
+            height: 500.0,
+            theme: Theme::light(),
+            title: None,
+            subtitle: None,
+            x_title: None,
+            y_title: None,
+            x_scale: ScaleOptions::new(ScaleKind::Linear).include_zero(false),
+            y_scale: ScaleOptions::new(ScaleKind::Linear).include_zero(false),
+            marker: Marker::Circle,
+            marker_size: 8.0,
+            bubble_size: None,
+            points: Vec::new(),
+        }
+    }
+
+    pub fn width(mut self, width: f32) -> ScatterBuilder {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: f32) -> ScatterBuilder {
+        self.height = height;
+        self
+    }
+
+    pub fn points(mut self, points: Vec<Point>) -> ScatterBuilder {
+        self.points.extend(points);
+        self
+    }
+
+    /// Shape of every point, circles by default
+    pub fn marker(mut self, marker: Marker) -> ScatterBuilder {
+        self.marker = marker;
+        self
+    }
+
+    /// Size of points which don't set their own
+    pub fn marker_size(mut self, size: f32) -> ScatterBuilder {
+        self.marker_size = size;
+        self
+    }
+
+    /// Draw a bubble graph, the point values are mapped to the marker
+    /// area with the largest value `max_size` pixels across
+    pub fn bubbles(mut self, max_size: f32) -> ScatterBuilder {
+        self.bubble_size = Some(max_size);
+        self
+    }
+
+    /// Unlike bars and lines, neither axis includes zero by default
+    pub fn x_scale<S>(mut self, scale: S) -> ScatterBuilder where S: Into<ScaleOptions> {
+        self.x_scale = scale.into();
+        self
+    }
+
+    pub fn y_scale<S>(mut self, scale: S) -> ScatterBuilder where S: Into<ScaleOptions> {
+        self.y_scale = scale.into();
+        self
+    }
+
+    /// Colors and fonts, `Theme::light()` by default
+    pub fn theme(mut self, theme: Theme) -> ScatterBuilder {
+        self.theme = theme;
+        self
+    }
+
+    pub fn title<S>(mut self, title: S) -> ScatterBuilder where S: Into<String> {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn subtitle<S>(mut self, subtitle: S) -> ScatterBuilder where S: Into<String> {
+        self.subtitle = Some(subtitle.into());
+        self
+    }
+
+    pub fn x_title<S>(mut self, title: S) -> ScatterBuilder where S: Into<String> {
+        self.x_title = Some(title.into());
+        self
+    }
+
+    /// Title drawn rotated along the y-axis
+    pub fn y_title<S>(mut self, title: S) -> ScatterBuilder where S: Into<String> {
+        self.y_title = Some(title.into());
+        self
+    }
+
+    pub fn build(self) -> Scatter {
+        let padding = Padding::with_same(15.0);
+        let theme = self.theme;
+        let (width, height) = (self.width, self.height);
+        let points = self.points;
+        let content = Coord::from_padding(&padding, (0.0, 0.0, width, height));
+        let title = Title::new(self.title, self.subtitle);
+        let (content, title_body) = title.split(&content);
+        let size_scale = self.bubble_size.map(|max_size| {
+            let (_, max) = ScatterBuilder::min_max(points.iter().filter_map(|p| p.value));
+            SqrtScale::new(0.0, max).with_range(0.0, max_size)
+        });
+        let (content, size_legend) = match size_scale {
+            Some(ref scale) => {
+                let bubbles = scale.ticks()
+                    .iter()
+                    .map(|v| (scale.tick_label(*v), scale.offset(*v)))
+                    .collect();
+                let legend = SizeLegend::new(bubbles);
+                let (content, body) = legend.split(&content, &theme);
+                (content, Some((legend, body)))
+            },
+            None => (content, None),
+        };
+
+        let axes = {
+            let (min_x, max_x) = ScatterBuilder::min_max(points.iter().map(|p| p.x));
+            let (min_y, max_y) = ScatterBuilder::min_max(points.iter().map(|p| p.y));
+
+            let x_opt = AxisOption {
+                scale: self.x_scale.scale(min_x, max_x),
+                kind: AxisKind::Value,
+                title: self.x_title,
+                label_position: LabelPosition::Normal,
+            };
+
+            let y_opt = AxisOption {
+                scale: self.y_scale.scale(min_y, max_y),
+                kind: AxisKind::Value,
+                title: self.y_title,
+                label_position: LabelPosition::Normal,
+            };
+
+            Axes2d::new((content.width, content.height), x_opt, y_opt, &theme)
+        };
+
+        let body = axes.body();
+
+        Scatter {
+            size: Size { width: width, height: height },
+            title: (title, title_body),
+            theme: theme,
+            content: content,
+            axes: axes,
+            body: body,
+            marker: self.marker,
+            marker_size: self.marker_size,
+            size_scale: size_scale,
+            size_legend: size_legend,
+            points: points,
+        }
+    }
+
+    /// Range of the values, `(0, 1)` if there are none
+    fn min_max<I>(values: I) -> (f32, f32) where I: Iterator<Item = f32> {
+        let (min, max) = values.fold((MAX, MIN), |(min, max), v| (min.min(v), max.max(v)));
+
+        if min > max {
+            (0.0, 1.0)
+        } else {
+            (min, max)
+        }
+    }
+}
+
+pub struct Scatter {
+    size: Size,
+    title: (Title, Coord),
+    theme: Theme,
+    marker: Marker,
+    marker_size: f32,
+    /// Maps point values to marker sizes in bubble graphs
+    size_scale: Option<Box<Scale>>,
+    size_legend: Option<(SizeLegend, Coord)>,
+    points: Vec<Point>,
+    content: Coord,
+    body: Coord,
+    axes: Axes2d,
+}
+
+impl Scatter {
+    /// Bubbles are drawn largest first so that the small ones stay visible
+    fn markers(&self) -> Vec<HTML> {
+        let h = self.body.height;
+        let mut markers = self.points
+            .iter()
+            .map(|p| (self.axes.x.scale.offset(p.x),
+                      h - self.axes.y.scale.offset(p.y),
+                      self.size(p),
+                      p))
+            .collect::<Vec<_>>();
+
+        if self.size_scale.is_some() {
+            markers.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(Ordering::Equal));
+        }
+
+        markers
+            .into_iter()
+            .map(|(x, y, size, p)| {
+                let color = match p.color {
+                    Some(ref c) => c.clone(),
+                    None        => self.theme.color(0),
+                };
+
+                self.marker.render(x, y, size, &color, None)
+            })
+            .collect()
+    }
+
+    fn size(&self, p: &Point) -> f32 {
+        match (p.size, p.value, &self.size_scale) {
+            (Some(size), _, _)                 => size,
+            (None, Some(v), &Some(ref scale))  => scale.offset(v),
+            _                                  => self.marker_size,
+        }
+    }
+}
+
+impl Graph for Scatter {
+    fn into_html(&self) -> HTML {
+        let markers = self.markers();
+
+        html! {
+            svg width=(self.size.width) height=(self.size.height) xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" font-family=(self.theme.font_family) font-size=(self.theme.font_size) {
+
+                (self.theme.background(&self.size))
+                (self.title.0.render(&self.title.1, &self.theme))
+
+                g.content transform=(Tools::tr(self.content.x, self.content.y)) {
+                    (self.axes.render())
+
+                    g.scatter-box transform=(Tools::tr(self.body.x, self.body.y)) {
+                        @for marker in markers {
+                            (marker)
+                        }
+                    }
+                }
+
+                @if let Some((ref legend, ref legend_body)) = self.size_legend {
+                    (legend.render(legend_body, &self.theme))
+                }
+            }
+        }
+    }
+}
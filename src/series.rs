@@ -0,0 +1,87 @@
+use entry::Entry;
+use theme::Theme;
+
+/// Named list of entries that is drawn as a separate path.
+pub struct Series {
+    pub name: String,
+    pub entries: Vec<Entry>,
+    pub color: Option<String>,
+    pub gaps: Gaps,
+    pub y_axis: YAxis,
+}
+
+impl Series {
+    pub fn new<S>(name: S, entries: Vec<Entry>) -> Series where S: Into<String> {
+        Series {
+            name: name.into(),
+            entries: entries,
+            color: None,
+            gaps: Gaps::Break,
+            y_axis: YAxis::Left,
+        }
+    }
+
+    pub fn color<S>(mut self, color: S) -> Series where S: Into<String> {
+        self.color = Some(color.into());
+        self
+    }
+
+    /// How a line is drawn over missing values, broken by default
+    pub fn gaps(mut self, gaps: Gaps) -> Series {
+        self.gaps = gaps;
+        self
+    }
+
+    /// Value axis the series is drawn against, the left one by default.
+    /// Vertical bar graphs draw series of the right axis as lines.
+    pub fn y_axis(mut self, y_axis: YAxis) -> Series {
+        self.y_axis = y_axis;
+        self
+    }
+
+    /// Entry values with the missing ones replaced by zero
+    /// if the series asks for it
+    pub fn values(&self) -> Vec<Option<f32>> {
+        self.entries
+            .iter()
+            .map(|e| match (e.value, &self.gaps) {
+                (None, &Gaps::Zero) => Some(0.0),
+                (v, _)              => v,
+            })
+            .collect()
+    }
+
+    /// Series color or the theme palette color for the `i`-th series
+    pub fn color_or_palette(&self, theme: &Theme, i: usize) -> String {
+        match self.color {
+            Some(ref c) => c.clone(),
+            None        => theme.color(i),
+        }
+    }
+}
+
+/// Handling of missing values in line graphs
+pub enum Gaps {
+    /// Start a new subpath after the gap
+    Break,
+    /// Join the points around the gap
+    Connect,
+    /// Draw missing values as zero
+    Zero,
+}
+
+/// Side of the value axis a series belongs to
+pub enum YAxis {
+    Left,
+    /// Secondary axis with its own scale
+    Right,
+}
+
+impl YAxis {
+    pub fn is_right(&self) -> bool {
+        match *self {
+            YAxis::Left  => false,
+            YAxis::Right => true,
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! Text width estimation for layout. SVG has no way to measure text before
+//! it is rendered, so widths are calculated from the Helvetica advance widths
+//! which are close to the default sans-serif font of most browsers.
+
+use graph::Coord;
+
+/// Advance widths of ASCII characters from ' ' to '~' in 1/1000 of em
+const ADVANCE: [u16; 95] = [
+    // ' ' ... '/'
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    // '0' ... '9'
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556,
+    // ':' ... '@'
+    278, 278, 584, 584, 584, 556, 1015,
+    // 'A' ... 'Z'
+    667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833,
+    722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611,
+    // '[' ... '`'
+    278, 278, 278, 469, 556, 333,
+    // 'a' ... 'z'
+    556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833,
+    556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500,
+    // '{' ... '~'
+    334, 260, 334, 584,
+];
+
+/// Used for characters outside of the table
+const DEFAULT_ADVANCE: u16 = 556;
+
+const ELLIPSIS: char = '\u{2026}';
+
+pub struct TextMetrics;
+
+impl TextMetrics {
+    /// Width of the text in pixels
+    pub fn width(text: &str, font_size: f32) -> f32 {
+        let em = text
+            .chars()
+            .map(|c| {
+                let code = c as u32;
+                if code >= 32 && code <= 126 {
+                    ADVANCE[(code - 32) as usize]
+                } else {
+                    DEFAULT_ADVANCE
+                }
+            })
+            .fold(0, |acc, w| acc + w as u32);
+
+        em as f32 / 1000.0 * font_size
+    }
+
+    /// Width of the widest text
+    pub fn max_width<'a, I>(texts: I, font_size: f32) -> f32
+        where I: Iterator<Item = &'a String> {
+
+        texts.fold(0.0, |w: f32, t| w.max(TextMetrics::width(t, font_size)))
+    }
+
+    /// Text cut with an ellipsis so that it isn't wider than `max_width`
+    pub fn truncate(text: &str, max_width: f32, font_size: f32) -> String {
+        if TextMetrics::width(text, font_size) <= max_width {
+            return text.to_string();
+        }
+
+        let mut chars = text.chars().collect::<Vec<char>>();
+        loop {
+            let kept = chars.iter().cloned().collect::<String>();
+            let truncated = format!("{}{}", kept.trim_right_matches(' '), ELLIPSIS);
+            if chars.is_empty() || TextMetrics::width(&truncated, font_size) <= max_width {
+                return truncated;
+            }
+            chars.pop();
+        }
+    }
+
+    /// Words of the text put on as few lines as possible without any
+    /// line wider than `max_width`, a longer word gets its own line
+    pub fn wrap(text: &str, max_width: f32, font_size: f32) -> Vec<String> {
+        let mut lines: Vec<String> = Vec::new();
+
+        for word in text.split_whitespace() {
+            let joined = match lines.last() {
+                Some(line) => format!("{} {}", line, word),
+                None       => String::new(),
+            };
+
+            if !lines.is_empty() && TextMetrics::width(&joined, font_size) <= max_width {
+                *lines.last_mut().unwrap() = joined;
+            } else {
+                lines.push(word.to_string());
+            }
+        }
+
+        lines
+    }
+
+    /// Indices of the label boxes left after dropping every box
+    /// that overlaps one kept before it
+    pub fn thin(boxes: &[Coord]) -> Vec<usize> {
+        let mut kept: Vec<usize> = Vec::new();
+
+        for (i, b) in boxes.iter().enumerate() {
+            let overlaps = kept.iter().any(|&k| {
+                let a = &boxes[k];
+                a.x < b.x + b.width && b.x < a.x + a.width &&
+                a.y < b.y + b.height && b.y < a.y + a.height
+            });
+
+            if !overlaps {
+                kept.push(i);
+            }
+        }
+
+        kept
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn width() {
+        assert_eq!(TextMetrics::width("", 12.0), 0.0);
+        assert_eq!(TextMetrics::width("0", 10.0), 5.56);
+        assert_eq!(TextMetrics::width("Wi", 10.0), 9.44 + 2.22);
+        assert_eq!(TextMetrics::width("1,250,000", 12.0), (7.0 * 556.0 + 2.0 * 278.0) / 1000.0 * 12.0);
+        assert!(TextMetrics::width("mmm", 12.0) > TextMetrics::width("iii", 12.0));
+    }
+
+    #[test]
+    fn truncate() {
+        assert_eq!(TextMetrics::truncate("Monday", 100.0, 10.0), "Monday");
+        // "Mon" is 19.45px and the ellipsis 5.56px
+        assert_eq!(TextMetrics::truncate("Monday", 30.0, 10.0), "Mon\u{2026}");
+        assert_eq!(TextMetrics::truncate("Mo day", 25.0, 10.0), "Mo\u{2026}");
+        assert_eq!(TextMetrics::truncate("Monday", 1.0, 10.0), "\u{2026}");
+    }
+
+    #[test]
+    fn wrap() {
+        assert_eq!(TextMetrics::wrap("New York City", 60.0, 10.0), vec!["New York", "City"]);
+        assert_eq!(TextMetrics::wrap("New York City", 100.0, 10.0), vec!["New York City"]);
+        assert_eq!(TextMetrics::wrap("Supercalifragilistic is", 30.0, 10.0),
+                   vec!["Supercalifragilistic", "is"]);
+        assert!(TextMetrics::wrap("  ", 30.0, 10.0).is_empty());
+    }
+
+    #[test]
+    fn thin() {
+        let boxes = vec![
+            Coord { x: 0.0, y: 0.0, width: 20.0, height: 10.0 },
+            Coord { x: 15.0, y: 5.0, width: 20.0, height: 10.0 },
+            Coord { x: 30.0, y: 0.0, width: 20.0, height: 10.0 },
+            Coord { x: 30.0, y: 10.0, width: 20.0, height: 10.0 },
+        ];
+        assert_eq!(TextMetrics::thin(&boxes), vec![0, 2, 3]);
+        assert_eq!(TextMetrics::thin(&[]), vec![]);
+    }
+}
@@ -0,0 +1,136 @@
+use graph::{Size, HTML};
+
+/// Colors, fonts and stroke widths shared by all graphs
+#[derive(Clone)]
+pub struct Theme {
+    /// Colors of series, entries and slices, repeated when exhausted
+    pub palette: Vec<String>,
+    /// Fill of the bars when there is a single series
+    pub bar_color: String,
+    pub background: Option<String>,
+    /// Fill behind a legend placed inside the graph
+    pub legend_background: String,
+    pub axis_color: String,
+    pub grid_color: String,
+    pub minor_grid_color: String,
+    pub text_color: String,
+    pub subtitle_color: String,
+    /// Outline drawn between pie slices
+    pub slice_stroke: String,
+    pub font_family: String,
+    pub font_size: f32,
+    pub title_font_size: f32,
+    pub subtitle_font_size: f32,
+    pub line_width: f32,
+    pub grid_width: f32,
+}
+
+impl Theme {
+    pub fn light() -> Theme {
+        Theme {
+            palette: strings(&[
+                "rgb(237,10,63)",
+                "rgb(231,114,0)",
+                "rgb(254,216,93)",
+                "rgb(1,120,111)",
+                "rgb(165,7,44)",
+                "rgb(243,184,127)",
+                "rgb(66,75,77)",
+                "rgb(203,172,74)",
+                "rgb(153,201,197)",
+                "rgb(129,135,136)",
+            ]),
+            bar_color: "rgba(131,43,189,0.6)".to_string(),
+            background: None,
+            legend_background: "rgb(255,255,255)".to_string(),
+            axis_color: "rgb(13,16,17)".to_string(),
+            grid_color: "rgb(203,206,206)".to_string(),
+            minor_grid_color: "rgb(233,235,235)".to_string(),
+            text_color: "rgb(12,12,12)".to_string(),
+            subtitle_color: "rgb(66,75,77)".to_string(),
+            slice_stroke: "rgba(245,245,245,0.8)".to_string(),
+            font_family: "sans-serif".to_string(),
+            font_size: 12.0,
+            title_font_size: 16.0,
+            subtitle_font_size: 12.0,
+            line_width: 2.0,
+            grid_width: 1.0,
+        }
+    }
+
+    pub fn dark() -> Theme {
+        Theme {
+            palette: strings(&[
+                "rgb(255,99,132)",
+                "rgb(255,159,64)",
+                "rgb(255,205,86)",
+                "rgb(75,192,192)",
+                "rgb(54,162,235)",
+                "rgb(153,102,255)",
+                "rgb(201,203,207)",
+                "rgb(255,120,200)",
+                "rgb(120,220,120)",
+                "rgb(240,240,120)",
+            ]),
+            bar_color: "rgba(153,102,255,0.7)".to_string(),
+            background: Some("rgb(30,32,36)".to_string()),
+            legend_background: "rgb(30,32,36)".to_string(),
+            axis_color: "rgb(200,203,207)".to_string(),
+            grid_color: "rgb(70,74,80)".to_string(),
+            minor_grid_color: "rgb(50,53,58)".to_string(),
+            text_color: "rgb(230,230,230)".to_string(),
+            subtitle_color: "rgb(160,165,170)".to_string(),
+            slice_stroke: "rgba(30,32,36,0.8)".to_string(),
+            .. Theme::light()
+        }
+    }
+
+    pub fn pastel() -> Theme {
+        Theme {
+            palette: strings(&[
+                "rgb(179,205,227)",
+                "rgb(251,180,174)",
+                "rgb(204,235,197)",
+                "rgb(222,203,228)",
+                "rgb(254,217,166)",
+                "rgb(229,216,189)",
+                "rgb(253,218,236)",
+                "rgb(190,186,218)",
+                "rgb(255,255,204)",
+                "rgb(242,242,242)",
+            ]),
+            bar_color: "rgb(179,205,227)".to_string(),
+            slice_stroke: "rgb(255,255,255)".to_string(),
+            .. Theme::light()
+        }
+    }
+
+    /// Palette color for the `i`-th series, starts from the beginning when
+    /// the palette runs out.
+    pub fn color(&self, i: usize) -> String {
+        if self.palette.is_empty() {
+            self.text_color.clone()
+        } else {
+            self.palette[i % self.palette.len()].clone()
+        }
+    }
+
+    /// Rectangle filling the whole graph if the theme has a background
+    pub fn background(&self, size: &Size) -> HTML {
+        html! {
+            @if let Some(ref background) = self.background {
+                rect.background x="0" y="0" width=(size.width) height=(size.height) fill=(background) {}
+            }
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::light()
+    }
+}
+
+fn strings(colors: &[&str]) -> Vec<String> {
+    colors.iter().map(|c| c.to_string()).collect()
+}
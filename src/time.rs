@@ -0,0 +1,369 @@
+//! Time scale with ticks on calendar boundaries. All calculations are in UTC.
+
+use scale::Scale;
+
+const MONTHS: [&'static str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun",
+    "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+const MINUTE: i64 = 60;
+const HOUR: i64 = 60 * MINUTE;
+const DAY: i64 = 24 * HOUR;
+const WEEK: i64 = 7 * DAY;
+
+/// Offset of the first Monday (1970-01-05) from the Unix epoch, a Thursday
+const MONDAY: i64 = 4 * DAY;
+
+/// Default minimum distance between the ticks in pixels
+const TICK_SPACING: f32 = 80.0;
+
+/// Tick interval candidates from the finest to the coarsest
+const INTERVALS: [Interval; 23] = [
+    Interval::Second(1),
+    Interval::Second(5),
+    Interval::Second(15),
+    Interval::Second(30),
+    Interval::Minute(1),
+    Interval::Minute(5),
+    Interval::Minute(15),
+    Interval::Minute(30),
+    Interval::Hour(1),
+    Interval::Hour(3),
+    Interval::Hour(6),
+    Interval::Hour(12),
+    Interval::Day(1),
+    Interval::Day(2),
+    Interval::Day(7),
+    Interval::Month(1),
+    Interval::Month(3),
+    Interval::Month(6),
+    Interval::Year(1),
+    Interval::Year(2),
+    Interval::Year(5),
+    Interval::Year(10),
+    Interval::Year(100),
+];
+
+/// Scale over Unix timestamps. To keep `f32` precision values passed to
+/// `offset` are seconds since the start of the domain.
+pub struct TimeScale {
+    origin: i64,
+    span: i64,
+    range: (f32, f32),
+    /// Minimum distance between the ticks in pixels
+    spacing: f32,
+}
+
+impl TimeScale {
+    pub fn new(start: i64, stop: i64) -> TimeScale {
+        TimeScale {
+            origin: start,
+            span: if stop > start { stop - start } else { 1 },
+            range: (0.0, 0.0),
+            spacing: TICK_SPACING,
+        }
+    }
+
+    /// Coarsest interval that keeps ticks at least `spacing` apart
+    fn interval(&self) -> Interval {
+        let max_ticks = ((self.range.1 - self.range.0).abs() / self.spacing).max(2.0);
+
+        for interval in INTERVALS.iter() {
+            if (self.span as f32) / (interval.seconds() as f32) <= max_ticks {
+                return *interval;
+            }
+        }
+
+        INTERVALS[INTERVALS.len() - 1]
+    }
+}
+
+impl Scale for TimeScale {
+    fn offset(&self, i: f32) -> f32 {
+        let ratio = i / (self.span as f32);
+        self.range.0 + ratio * (self.range.1 - self.range.0)
+    }
+
+    fn segment(&self) -> f32 {
+        (self.range.1 - self.range.0) / (self.span as f32)
+    }
+
+    fn ticks(&self) -> Vec<f32> {
+        let interval = self.interval();
+        let stop = self.origin + self.span;
+        let mut t = interval.floor(self.origin);
+        if t < self.origin {
+            t = interval.next(t);
+        }
+
+        let mut ticks = Vec::new();
+        while t <= stop {
+            ticks.push((t - self.origin) as f32);
+            t = interval.next(t);
+        }
+        ticks
+    }
+
+    /// Offsets above 2^24 seconds lose whole seconds in `f32`, so the
+    /// tick is snapped back to the nearest interval boundary. Multi-day
+    /// ticks can be a single day apart at the end of a month.
+    fn tick_label(&self, v: f32) -> String {
+        let interval = self.interval();
+        let t = self.origin + v.round() as i64;
+        let tolerance = (interval.seconds() / 2).min(DAY / 2);
+        interval.format(interval.floor(t + tolerance))
+    }
+
+    fn with_range(&self, start: f32, stop: f32) -> Box<Scale> {
+        Box::new(TimeScale { range: (start, stop), .. *self })
+    }
+
+    fn with_tick_spacing(&self, spacing: f32) -> Box<Scale> {
+        Box::new(TimeScale { spacing: spacing.max(TICK_SPACING), .. *self })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Interval {
+    Second(i64),
+    Minute(i64),
+    Hour(i64),
+    Day(i64),
+    Month(i64),
+    Year(i64),
+}
+
+impl Interval {
+    /// Approximate length, only used to pick an interval
+    fn seconds(&self) -> i64 {
+        match *self {
+            Interval::Second(n) => n,
+            Interval::Minute(n) => n * MINUTE,
+            Interval::Hour(n)   => n * HOUR,
+            Interval::Day(n)    => n * DAY,
+            Interval::Month(n)  => n * 30 * DAY,
+            Interval::Year(n)   => n * 365 * DAY,
+        }
+    }
+
+    /// Latest interval boundary at or before `t`
+    fn floor(&self, t: i64) -> i64 {
+        match *self {
+            Interval::Month(n) => {
+                let date = Date::from_timestamp(t);
+                let months = div_floor(date.year * 12 + date.month - 1, n) * n;
+                Date::new(div_floor(months, 12), months - div_floor(months, 12) * 12 + 1, 1)
+                    .timestamp()
+            },
+            Interval::Year(n)  => {
+                let date = Date::from_timestamp(t);
+                Date::new(div_floor(date.year, n) * n, 1, 1).timestamp()
+            },
+            // Weeks start on Monday
+            Interval::Day(7)   => div_floor(t - MONDAY, WEEK) * WEEK + MONDAY,
+            // Other multi-day steps restart on the first of every month
+            Interval::Day(n) if n > 1 => {
+                let date = Date::from_timestamp(t);
+                Date::new(date.year, date.month, div_floor(date.day - 1, n) * n + 1)
+                    .timestamp()
+            },
+            _ => {
+                let step = self.seconds();
+                div_floor(t, step) * step
+            },
+        }
+    }
+
+    fn next(&self, t: i64) -> i64 {
+        match *self {
+            Interval::Month(n) => {
+                let date = Date::from_timestamp(t);
+                let months = date.year * 12 + date.month - 1 + n;
+                Date::new(div_floor(months, 12), months - div_floor(months, 12) * 12 + 1, 1)
+                    .timestamp()
+            },
+            Interval::Year(n)  => {
+                let date = Date::from_timestamp(t);
+                Date::new(date.year + n, 1, 1).timestamp()
+            },
+            Interval::Day(n) if n > 1 && n != 7 => {
+                let date = Date::from_timestamp(t);
+                let next = Date::from_timestamp(t + n * DAY);
+                if next.month == date.month {
+                    next.timestamp()
+                } else {
+                    Date::new(next.year, next.month, 1).timestamp()
+                }
+            },
+            _ => t + self.seconds(),
+        }
+    }
+
+    fn format(&self, t: i64) -> String {
+        let date = Date::from_timestamp(t);
+        let month = MONTHS[(date.month - 1) as usize];
+
+        match *self {
+            Interval::Second(_) => {
+                format!("{:02}:{:02}:{:02}", date.hour, date.minute, date.second)
+            },
+            Interval::Minute(_) | Interval::Hour(_) if date.is_midnight() => {
+                format!("{} {:02}", month, date.day)
+            },
+            Interval::Minute(_) | Interval::Hour(_) => {
+                format!("{:02}:{:02}", date.hour, date.minute)
+            },
+            Interval::Day(_)    => format!("{} {:02}", month, date.day),
+            Interval::Month(_)  => format!("{} {}", month, date.year),
+            Interval::Year(_)   => format!("{}", date.year),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Date {
+    year: i64,
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: i64,
+}
+
+impl Date {
+    fn new(year: i64, month: i64, day: i64) -> Date {
+        Date {
+            year: year,
+            month: month,
+            day: day,
+            hour: 0,
+            minute: 0,
+            second: 0,
+        }
+    }
+
+    // Conversions are based on http://howardhinnant.github.io/date_algorithms.html
+    fn from_timestamp(t: i64) -> Date {
+        let days = div_floor(t, DAY);
+        let secs = t - days * DAY;
+
+        let z = days + 719468;
+        let era = div_floor(z, 146097);
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        Date {
+            year: year,
+            month: month,
+            day: day,
+            hour: secs / HOUR,
+            minute: secs % HOUR / MINUTE,
+            second: secs % MINUTE,
+        }
+    }
+
+    fn timestamp(&self) -> i64 {
+        let y = if self.month <= 2 { self.year - 1 } else { self.year };
+        let era = div_floor(y, 400);
+        let yoe = y - era * 400;
+        let mp = (self.month + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + self.day - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        let days = era * 146097 + doe - 719468;
+
+        days * DAY + self.hour * HOUR + self.minute * MINUTE + self.second
+    }
+
+    fn is_midnight(&self) -> bool {
+        self.hour == 0 && self.minute == 0 && self.second == 0
+    }
+}
+
+fn div_floor(a: i64, b: i64) -> i64 {
+    let d = a / b;
+    if (a % b != 0) && ((a < 0) != (b < 0)) { d - 1 } else { d }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date() {
+        assert_eq!(Date::from_timestamp(0), Date::new(1970, 1, 1));
+        assert_eq!(Date::from_timestamp(951782400), Date::new(2000, 2, 29));
+        assert_eq!(Date::from_timestamp(-86400), Date::new(1969, 12, 31));
+        assert_eq!(Date::new(2016, 12, 31).timestamp(), 1483142400);
+        assert_eq!(Date::from_timestamp(1483142400 + 3725).minute, 2);
+    }
+
+    #[test]
+    fn interval() {
+        // 2016-12-31 13:45:00
+        let t = 1483142400 + 13 * HOUR + 45 * MINUTE;
+        assert_eq!(Interval::Hour(6).floor(t), 1483142400 + 12 * HOUR);
+        assert_eq!(Interval::Month(3).floor(t), Date::new(2016, 10, 1).timestamp());
+        assert_eq!(Interval::Month(3).next(Date::new(2016, 10, 1).timestamp()),
+                   Date::new(2017, 1, 1).timestamp());
+        assert_eq!(Interval::Year(10).floor(t), Date::new(2010, 1, 1).timestamp());
+
+        // 2016-12-26 was a Monday
+        assert_eq!(Interval::Day(7).floor(t), Date::new(2016, 12, 26).timestamp());
+        assert_eq!(Interval::Day(7).floor(Date::new(2016, 12, 26).timestamp()),
+                   Date::new(2016, 12, 26).timestamp());
+        assert_eq!(Interval::Day(2).floor(t), Date::new(2016, 12, 31).timestamp());
+        assert_eq!(Interval::Day(2).floor(Date::new(2017, 1, 2).timestamp()),
+                   Date::new(2017, 1, 1).timestamp());
+        assert_eq!(Interval::Day(2).next(Date::new(2016, 12, 31).timestamp()),
+                   Date::new(2017, 1, 1).timestamp());
+        assert_eq!(Interval::Day(2).next(Date::new(2017, 1, 1).timestamp()),
+                   Date::new(2017, 1, 3).timestamp());
+
+        assert_eq!(Interval::Minute(15).format(t), "13:45");
+        assert_eq!(Interval::Hour(1).format(1483142400), "Dec 31");
+        assert_eq!(Interval::Month(1).format(t), "Dec 2016");
+    }
+
+    #[test]
+    fn time_scale() {
+        let start = Date::new(2016, 1, 1).timestamp();
+        let stop = Date::new(2016, 12, 31).timestamp();
+        let scale = TimeScale::new(start, stop).with_range(0.0, 500.0);
+
+        assert_eq!(scale.offset(0.0), 0.0);
+        assert_eq!(scale.offset((stop - start) as f32), 500.0);
+        assert_eq!(scale.ticks().len(), 4);
+        assert_eq!(scale.tick_label(0.0), "Jan 2016");
+    }
+
+    #[test]
+    fn time_scale_long_span() {
+        // Offsets this large don't keep whole seconds in f32
+        let start = Date::new(2019, 3, 14).timestamp() + 7;
+        let stop = Date::new(2024, 6, 1).timestamp() + 3;
+        let scale = TimeScale::new(start, stop).with_range(0.0, 500.0);
+        let labels = scale.ticks().iter().map(|v| scale.tick_label(*v)).collect::<Vec<_>>();
+        assert_eq!(labels, vec!["2020", "2021", "2022", "2023", "2024"]);
+
+        let scale = TimeScale::new(start, stop).with_range(0.0, 2000.0);
+        let labels = scale.ticks().iter().map(|v| scale.tick_label(*v)).collect::<Vec<_>>();
+        assert_eq!(labels.len(), 21);
+        assert_eq!(labels[0], "Apr 2019");
+        assert_eq!(labels[3], "Jan 2020");
+        assert_eq!(labels[20], "Apr 2024");
+
+        // Every other day, restarting on the first of the month
+        let start = Date::new(2016, 1, 20).timestamp();
+        let stop = Date::new(2016, 2, 10).timestamp();
+        let scale = TimeScale::new(start, stop).with_range(0.0, 900.0);
+        let labels = scale.ticks().iter().map(|v| scale.tick_label(*v)).collect::<Vec<_>>();
+        assert_eq!(&labels[4..7], &["Jan 29", "Jan 31", "Feb 01"]);
+    }
+}
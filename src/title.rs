@@ -0,0 +1,61 @@
+use graph::{Tools, Coord, HTML};
+use theme::Theme;
+
+const TITLE_HEIGHT: f32 = 22.0;
+const SUBTITLE_HEIGHT: f32 = 18.0;
+/// Space between the title and the graph
+const MARGIN: f32 = 10.0;
+
+/// Title and subtitle centered above the graph
+pub struct Title {
+    title: Option<String>,
+    subtitle: Option<String>,
+}
+
+impl Title {
+    pub fn new(title: Option<String>, subtitle: Option<String>) -> Title {
+        Title {
+            title: title,
+            subtitle: subtitle,
+        }
+    }
+
+    fn height(&self) -> f32 {
+        let title = if self.title.is_some() { TITLE_HEIGHT } else { 0.0 };
+        let subtitle = if self.subtitle.is_some() { SUBTITLE_HEIGHT } else { 0.0 };
+
+        if title + subtitle > 0.0 {
+            title + subtitle + MARGIN
+        } else {
+            0.0
+        }
+    }
+
+    /// Reserve space at the top of the area, returns the area left
+    /// for the graph and the title box
+    pub fn split(&self, area: &Coord) -> (Coord, Coord) {
+        let h = self.height();
+
+        (
+            Coord { y: area.y + h, height: area.height - h, .. *area },
+            Coord { height: h, .. *area },
+        )
+    }
+
+    pub fn render(&self, area: &Coord, theme: &Theme) -> HTML {
+        let x = area.x + area.width / 2.0;
+        let subtitle_y = if self.title.is_some() { TITLE_HEIGHT } else { 0.0 };
+
+        html! {
+            g.title transform=(Tools::tr(x, area.y)) {
+                @if let Some(ref title) = self.title {
+                    text.title y="16" text-anchor="middle" font-size=(theme.title_font_size) font-weight="bold" fill=(theme.text_color) (title)
+                }
+
+                @if let Some(ref subtitle) = self.subtitle {
+                    text.subtitle y=(subtitle_y + 13.0) text-anchor="middle" font-size=(theme.subtitle_font_size) fill=(theme.subtitle_color) (subtitle)
+                }
+            }
+        }
+    }
+}
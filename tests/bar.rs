@@ -1,6 +1,8 @@
 extern crate svg_graph;
 
-use svg_graph::{Graph, BarBuilder, Entry};
+mod common;
+
+use svg_graph::{Graph, BarBuilder, Entry, Series, ScaleKind, Orientation, YAxis};
 
 #[test]
 fn bar_graph_to_file() {
@@ -19,6 +21,207 @@ fn bar_graph_to_file() {
     }
 }
 
+#[test]
+fn bar_graph_log_scale_to_file() {
+    let entries = vec![
+        Entry::new("Login", 12),
+        Entry::new("Search", 4500),
+        Entry::new("Feed", 10000000),
+        Entry::new("Upload", 320),
+    ];
+    let bar = BarBuilder::new()
+                .y_scale(ScaleKind::Log(10.0))
+                .entries(entries)
+                .build();
+    match bar.into_file("./images/bar_log.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // Ticks at the powers of ten around the data
+    let svg = bar.into_string();
+    let ticks = common::tick_labels(&svg, "y-line");
+    assert_eq!(ticks, vec!["10", "100", "1000", "10000", "100000", "1000000", "10000000"]);
+
+    // Bars grow from 10 and their heights follow the logarithm
+    let heights = common::numbers(&svg, "<rect class=\"bar\"", "height");
+    let top = common::tick(&svg, "y-line", "10").1 - common::tick(&svg, "y-line", "10000000").1;
+    let values = [12.0f32, 4500.0, 10000000.0, 320.0];
+    for (h, v) in heights.iter().zip(values.iter()) {
+        common::assert_close(*h, top * (v.log10() - 1.0) / 6.0);
+    }
+}
+
+#[test]
+fn bar_graph_stacked_to_file() {
+    let desktop = vec![
+        Entry::new("Q1", 120.5),
+        Entry::new("Q2", 140),
+        Entry::new("Q3", 90),
+        Entry::new("Q4", 160),
+    ];
+    let mobile = vec![
+        Entry::new("Q1", 80),
+        Entry::new("Q2", 110),
+        Entry::new("Q3", 130),
+        Entry::new("Q4", 150),
+    ];
+    let refunds = vec![
+        Entry::new("Q1", -20),
+        Entry::new("Q2", -35),
+        Entry::new("Q3", -10),
+        Entry::new("Q4", -40),
+    ];
+    let bar = BarBuilder::new()
+                .series(Series::new("Desktop", desktop))
+                .series(Series::new("Mobile", mobile))
+                .series(Series::new("Refunds", refunds))
+                .stacked()
+                .build();
+    match bar.into_file("./images/bar_stacked.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    let svg = bar.into_string();
+    let tops = common::attrs(&svg, "<g class=\"bar-box\"", "transform")
+        .iter()
+        .map(|t| common::translate(t).1)
+        .collect::<Vec<f32>>();
+    let heights = common::numbers(&svg, "<rect class=\"bar\"", "height");
+    let zero = common::tick(&svg, "y-line", "0").1;
+    assert_eq!(tops.len(), 12);
+
+    // Q1: desktop from zero, mobile on top of it, refunds below zero
+    common::assert_close(tops[0] + heights[0], zero);
+    common::assert_close(tops[1] + heights[1], tops[0]);
+    common::assert_close(tops[2], zero);
+    common::assert_close(heights[0] / heights[2], 120.5 / 20.0);
+
+    // The domain covers the highest total (Q4, 310) and the lowest (-40)
+    assert!(svg.contains(">350</text>"));
+    assert!(svg.contains(">-50</text>"));
+
+    // One palette color per series
+    let fills = common::attrs(&svg, "<rect class=\"bar\"", "fill");
+    assert!(fills[0] != fills[1] && fills[1] != fills[2] && fills[0] != fills[2]);
+    assert_eq!(fills[0], fills[3]);
+}
+
+#[test]
+fn bar_graph_grouped_to_file() {
+    let entries = |a, b, c| vec![
+        Entry::new("2014", a),
+        Entry::new("2015", b),
+        Entry::new("2016", c),
+    ];
+    let bar = BarBuilder::new()
+                .series(Series::new("Europe", entries(30, 42, 51)))
+                .series(Series::new("Asia", entries(25, 38, 60)))
+                .series(Series::new("Americas", entries(40, 35, 44)))
+                .group_padding(0.2)
+                .title("Revenue")
+                .subtitle("Yearly revenue per region")
+                .x_title("Year")
+                .y_title("Revenue, M€")
+                .build();
+    match bar.into_file("./images/bar_grouped.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // Bars are ordered by series, then by category
+    let svg = bar.into_string();
+    let lefts = common::attrs(&svg, "<g class=\"bar-box\"", "transform")
+        .iter()
+        .map(|t| common::translate(t).0)
+        .collect::<Vec<f32>>();
+    let xs = common::numbers(&svg, "<rect class=\"bar\"", "x");
+    let widths = common::numbers(&svg, "<rect class=\"bar\"", "width");
+    assert_eq!(widths.len(), 9);
+
+    // 10% of the band on each side, three bars and two gaps of 10%
+    // of a bar share the rest
+    let band = lefts[1] - lefts[0];
+    let w = band * 0.8 / 3.2;
+    assert!(widths.iter().all(|width| (width - w).abs() < 0.01));
+    common::assert_close(xs[0], band * 0.1);
+    common::assert_close(xs[3], band * 0.1 + w * 1.1);
+    common::assert_close(xs[6], band * 0.1 + w * 2.2);
+    common::assert_close(lefts[3], lefts[0]);
+
+    assert_eq!(common::count(&svg, "<g class=\"legend-item\""), 3);
+    assert!(svg.contains(">Americas</text>"));
+}
+
+#[test]
+fn bar_graph_horizontal_to_file() {
+    let entries = vec![
+        Entry::new("auth-service", 320),
+        Entry::new("/api/v1/users/search", 1250),
+        Entry::new("billing-worker", 87),
+        Entry::new("notifications", 640),
+    ];
+    let bar = BarBuilder::new()
+                .orientation(Orientation::Horizontal)
+                .entries(entries)
+                .build();
+    match bar.into_file("./images/bar_horizontal.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // Categories are the y labels and the values run along the x-axis
+    let svg = bar.into_string();
+    common::tick(&svg, "y-line", "/api/v1/users/search");
+    common::tick(&svg, "x-line", "1000");
+    assert_eq!(common::tick(&svg, "x-line", "0").0, 0.0);
+
+    let boxes = common::attrs(&svg, "<g class=\"bar-box\"", "transform")
+        .iter()
+        .map(|t| common::translate(t))
+        .collect::<Vec<(f32, f32)>>();
+    let widths = common::numbers(&svg, "<rect class=\"bar\"", "width");
+    let heights = common::numbers(&svg, "<rect class=\"bar\"", "height");
+    assert!(boxes.iter().all(|b| b.0 == boxes[0].0));
+    assert!(boxes[1].1 > boxes[0].1);
+    assert!(heights.iter().all(|h| *h == heights[0]));
+    common::assert_close(widths[0] / widths[1], 320.0 / 1250.0);
+}
+
+#[test]
+fn bar_graph_horizontal_wide_ticks() {
+    let entries = vec![
+        Entry::new("North", 320000),
+        Entry::new("South", 1000000),
+        Entry::new("East", 870000),
+    ];
+    let bar = BarBuilder::new()
+                .orientation(Orientation::Horizontal)
+                .width(900.0)
+                .entries(entries)
+                .build();
+    let svg = bar.into_string();
+
+    // Every label fits between its neighbours, e.g. "1000000" is
+    // about 47px wide at the default font size
+    let xs = common::attrs(&svg, "<g class=\"x-line\"", "transform")
+        .iter()
+        .map(|t| common::translate(t).0)
+        .collect::<Vec<f32>>();
+    common::tick(&svg, "x-line", "1000000");
+    assert!(xs.len() > 2);
+    assert!(xs.windows(2).all(|w| w[1] - w[0] >= 50.0), "{:?}", xs);
+}
+
 //#[test]
 //fn bar_graph_render_empty() {
     //let entries = vec![];
@@ -38,3 +241,138 @@ fn bar_graph_to_file() {
     //let s = bar.render();
     //assert_eq!(s, "<svg><g></g><g></g><g></g></svg>");
 //}
+
+#[test]
+fn bar_graph_missing_to_file() {
+    let entries = vec![
+        Entry::new("Jan", 40),
+        Entry::missing("Feb"),
+        Entry::new("Mar", 55),
+        Entry::new("Apr", 32),
+    ];
+    let bar = BarBuilder::new()
+                .entries(entries)
+                .build();
+    match bar.into_file("./images/bar_missing.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    let svg = bar.into_string();
+    assert_eq!(common::count(&svg, "<rect class=\"bar\""), 3);
+    assert!(svg.contains(">Feb</text>"));
+}
+
+#[test]
+fn bar_graph_long_labels_to_file() {
+    let entries = vec![
+        Entry::new("North America", 420),
+        Entry::new("South America", 180),
+        Entry::new("Western Europe", 390),
+        Entry::new("Eastern Europe", 150),
+        Entry::new("Middle East and North Africa", 120),
+        Entry::new("Sub-Saharan Africa", 90),
+        Entry::new("South Asia", 260),
+        Entry::new("East Asia and Pacific", 510),
+    ];
+    let bar = BarBuilder::new().entries(entries).build();
+    match bar.into_file("./images/bar_long_labels.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // Too wide for their slots, the labels are rotated by 45 degrees
+    // and the longest one is truncated
+    let svg = bar.into_string();
+    let rotations = common::attrs(&svg, "<text class=\"label\" transform=\"rotate(", "transform");
+    assert_eq!(rotations.len(), 8);
+    assert!(rotations.iter().all(|r| r.starts_with("rotate(-45,")));
+    let labels = common::tick_labels(&svg, "x-line");
+    assert!(labels.contains(&"North America".to_string()));
+    assert!(labels.iter().any(|l| l.starts_with("Middle East") && l.ends_with("\u{2026}")));
+}
+
+#[test]
+fn bar_graph_secondary_axis_to_file() {
+    let revenue = vec![
+        Entry::new("Jan", 48000),
+        Entry::new("Feb", 52000),
+        Entry::new("Mar", 61000),
+        Entry::new("Apr", 58000),
+    ];
+    let conversion = vec![
+        Entry::new("Jan", 2.1),
+        Entry::new("Feb", 2.4),
+        Entry::new("Mar", 3.2),
+        Entry::new("Apr", 3.5),
+    ];
+    let bar = BarBuilder::new()
+                .series(Series::new("Revenue", revenue))
+                .series(Series::new("Conversion rate", conversion).y_axis(YAxis::Right))
+                .y2_title("Conversion")
+                .build();
+    match bar.into_file("./images/bar_secondary_axis.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    let svg = bar.into_string();
+    assert_eq!(common::count(&svg, "<g class=\"y2-axis\""), 1);
+    assert!(svg.contains(">3.5</text>"));
+    assert!(svg.contains(">Conversion</text>"));
+
+    // Revenue is drawn as bars and the conversion rate as a line
+    // ending at the top of the right axis
+    assert_eq!(common::count(&svg, "<rect class=\"bar\""), 4);
+    let paths = common::attrs(&svg, "<path class=\"line\"", "d");
+    assert_eq!(paths.len(), 1);
+    assert_eq!(common::points(&paths[0])[3].1, 0.0);
+
+    // Its legend swatch is a line with the same marker
+    let legend = common::legend(&svg);
+    assert_eq!(common::count(legend, "<rect class=\"swatch\""), 1);
+    assert_eq!(common::count(legend, "<line class=\"swatch\""), 1);
+    assert_eq!(common::count(legend, "<circle class=\"marker\""), 1);
+}
+
+#[test]
+fn bar_graph_titles() {
+    let entries = || vec![
+        Entry::new("2014", 30),
+        Entry::new("2015", 42),
+    ];
+    let plain = BarBuilder::new().entries(entries()).build().into_string();
+    let titled = BarBuilder::new()
+                .entries(entries())
+                .title("Revenue")
+                .subtitle("Yearly revenue")
+                .x_title("Year")
+                .y_title("Revenue, M€")
+                .build()
+                .into_string();
+
+    let content = |svg: &str| common::translate(&common::attrs(svg, "<g class=\"content\"", "transform")[0]);
+    let axes = |svg: &str| common::translate(&common::attrs(svg, "<g class=\"axes\"", "transform")[0]);
+
+    // Space is reserved above the graph for the title and the subtitle
+    assert_eq!(content(&plain), (15.0, 15.0));
+    let subtitle_y = common::numbers(&titled, "<text class=\"subtitle\"", "y")[0];
+    assert!(content(&titled).1 > 15.0 + subtitle_y);
+
+    // The rotated y title moves the axes right
+    assert!(axes(&titled).0 >= axes(&plain).0 + 20.0);
+    let y_title = common::attrs(&titled, "<text class=\"axis-title\" transform", "transform");
+    assert!(y_title[0].ends_with("rotate(-90)"));
+
+    // The x title is below the x labels, which have their baseline
+    // 17px below the axis
+    let label_y = common::tick(&titled, "x-line", "2014").1 + 17.0;
+    let title_y = common::numbers(&titled, "<text class=\"axis-title\" x=", "y")[0];
+    assert!(title_y > label_y);
+}
@@ -0,0 +1,118 @@
+//! Helpers to inspect the rendered SVG in the graph tests
+#![allow(dead_code)]
+
+/// Value of `attr` for every element whose tag starts with `start`,
+/// e.g. `attrs(&svg, "<path class=\"line\"", "d")`
+pub fn attrs(svg: &str, start: &str, attr: &str) -> Vec<String> {
+    let pattern = format!(" {}=\"", attr);
+
+    svg.match_indices(start)
+        .filter_map(|(i, _)| {
+            let tag = &svg[i..i + svg[i..].find('>').unwrap_or(svg.len() - i)];
+            tag.find(&pattern).map(|j| {
+                let value = &tag[j + pattern.len()..];
+                value[..value.find('"').unwrap_or(value.len())].to_string()
+            })
+        })
+        .collect()
+}
+
+/// Markup before the legend, i.e. the graph itself
+pub fn without_legend(svg: &str) -> &str {
+    &svg[..svg.find("<g class=\"legend\"").unwrap_or(svg.len())]
+}
+
+/// Markup of the legend
+pub fn legend(svg: &str) -> &str {
+    &svg[svg.find("<g class=\"legend\"").expect("no legend")..]
+}
+
+/// Number of elements whose tag starts with `start`
+pub fn count(svg: &str, start: &str) -> usize {
+    svg.matches(start).count()
+}
+
+/// Points of a path made of `M` and `L` commands
+pub fn points(d: &str) -> Vec<(f32, f32)> {
+    d.split(|c| c == 'M' || c == 'L' || c == 'Z')
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let mut xy = p.split(',').map(|v| v.parse::<f32>().unwrap());
+            (xy.next().unwrap(), xy.next().unwrap())
+        })
+        .collect()
+}
+
+/// Arcs of a path as `(radius, sweep flag, end x, end y)`
+pub fn arcs(d: &str) -> Vec<(f32, u32, f32, f32)> {
+    d.split('A')
+        .skip(1)
+        .map(|a| {
+            let a = &a[..a.find(|c: char| c.is_alphabetic()).unwrap_or(a.len())];
+            let v = a.split(',').map(|v| v.parse::<f32>().unwrap()).collect::<Vec<f32>>();
+            (v[0], v[4] as u32, v[5], v[6])
+        })
+        .collect()
+}
+
+/// Point of the first `M` command of a path
+pub fn start(d: &str) -> (f32, f32) {
+    points(&d[..d[1..].find(|c: char| c.is_alphabetic()).map_or(d.len(), |i| i + 1)])[0]
+}
+
+/// Number of subpaths, i.e. `M` commands, in a path
+pub fn subpaths(d: &str) -> usize {
+    d.matches('M').count()
+}
+
+/// Translation of the axis line group (`y-line` or `x-line`) labeled `text`
+pub fn tick(svg: &str, class: &str, text: &str) -> (f32, f32) {
+    let start = format!("<g class=\"{}\" transform=\"translate(", class);
+    let label = format!(">{}</text>", text);
+
+    svg.split(&start[..])
+        .skip(1)
+        .find(|group| group[..group.find("</g>").unwrap_or(group.len())].contains(&label[..]))
+        .map(|group| translate(&format!("translate({}", group)))
+        .expect("no tick with this label")
+}
+
+/// Labels of the axis line groups (`y-line` or `x-line`) in document
+/// order, groups without text are left out
+pub fn tick_labels(svg: &str, class: &str) -> Vec<String> {
+    let start = format!("<g class=\"{}\"", class);
+
+    svg.split(&start[..])
+        .skip(1)
+        .filter_map(|group| {
+            let group = &group[..group.find("</g>").unwrap_or(group.len())];
+            group.find("<text").map(|i| {
+                let text = &group[i..];
+                let text = &text[text.find('>').unwrap() + 1..];
+                text[..text.find("</text>").unwrap()].to_string()
+            })
+        })
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Offset of a `translate(x,y)` transform
+pub fn translate(transform: &str) -> (f32, f32) {
+    let start = transform.find("translate(").expect("no translation") + "translate(".len();
+    let xy = &transform[start..start + transform[start..].find(')').unwrap()];
+    let mut xy = xy.split(',').map(|v| v.parse::<f32>().unwrap());
+    (xy.next().unwrap(), xy.next().unwrap())
+}
+
+/// Numeric values of `attr`, see `attrs`
+pub fn numbers(svg: &str, start: &str, attr: &str) -> Vec<f32> {
+    attrs(svg, start, attr)
+        .iter()
+        .map(|v| v.parse::<f32>().unwrap())
+        .collect()
+}
+
+/// Equal up to float rounding in the layout
+pub fn assert_close(a: f32, b: f32) {
+    assert!((a - b).abs() < 0.01, "{} != {}", a, b);
+}
@@ -1,6 +1,9 @@
 extern crate svg_graph;
 
-use svg_graph::{Graph, LineBuilder, Entry};
+mod common;
+
+use svg_graph::{Graph, LineBuilder, Entry, Series, Gaps, Legend, LegendPosition, Flow,
+                Interpolation, Marker, ScaleKind, ScaleOptions, YAxis};
 
 #[test]
 fn line_graph_to_file() {
@@ -26,3 +29,506 @@ fn line_graph_to_file() {
         Ok(_) => {}
     }
 }
+
+#[test]
+fn line_graph_series_to_file() {
+    let visits = vec![
+        Entry::new("Mon", 120),
+        Entry::new("Tue", 200),
+        Entry::new("Wed", 290),
+        Entry::new("Thu", 50),
+        Entry::new("Fri", 21),
+    ];
+    let signups = vec![
+        Entry::new("Mon", 20),
+        Entry::new("Tue", 40),
+        Entry::new("Wed", 35),
+        Entry::new("Thu", 10),
+        Entry::new("Fri", 5),
+    ];
+    let line = LineBuilder::new()
+                .series(Series::new("Visits", visits))
+                .series(Series::new("Signups", signups).color("rgb(0,0,255)"))
+                .legend(Legend::new().position(LegendPosition::Bottom).flow(Flow::Horizontal))
+                .build();
+    match line.into_file("./images/line_series.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // One line per series in its own color
+    let svg = line.into_string();
+    let strokes = common::attrs(&svg, "<path class=\"line\"", "stroke");
+    assert_eq!(strokes.len(), 2);
+    assert!(strokes[0] != strokes[1]);
+    assert_eq!(strokes[1], "rgb(0,0,255)");
+    assert!(svg.contains(">Visits</text>"));
+    assert!(svg.contains(">Signups</text>"));
+
+    // The y domain covers both series
+    let paths = common::attrs(&svg, "<path class=\"line\"", "d");
+    let visits = common::points(&paths[0]);
+    let signups = common::points(&paths[1]);
+    let zero = common::tick(&svg, "y-line", "0").1;
+    assert!(visits[2].1 >= 0.0 && visits[2].1 < signups[2].1);
+    assert!(signups.iter().all(|p| p.1 < zero));
+    let top = common::tick_labels(&svg, "y-line")
+        .iter()
+        .map(|t| t.parse::<f32>().unwrap())
+        .fold(0.0, f32::max);
+    assert!(top >= 290.0);
+}
+
+#[test]
+fn line_graph_entries_replace_series() {
+    let line = LineBuilder::new()
+                .entries(vec![Entry::new("Mon", 1), Entry::new("Tue", 2)])
+                .entries(vec![Entry::new("Mon", 3), Entry::new("Tue", 4)])
+                .build();
+    let svg = line.into_string();
+    assert_eq!(common::count(&svg, "<path class=\"line\""), 1);
+    assert!(svg.contains(">4</text>"));
+}
+
+#[test]
+fn line_graph_time_axis_to_file() {
+    // Uneven samples over two days starting at 2016-12-30 00:00 UTC
+    let start = 1483056000;
+    let entries = vec![
+        Entry::at(start, 12.7),
+        Entry::at(start + 1800, 14.1),
+        Entry::at(start + 4 * 3600, 9.3),
+        Entry::at(start + 13 * 3600, 21.0),
+        Entry::at(start + 30 * 3600, 17.5),
+        Entry::at(start + 47 * 3600, 11.2),
+    ];
+    let line = LineBuilder::new()
+                .entries(entries)
+                .build();
+    match line.into_file("./images/line_time.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // Points are placed by their timestamp, not by their index
+    let svg = line.into_string();
+    let paths = common::attrs(&svg, "<path class=\"line\"", "d");
+    let points = common::points(&paths[0]);
+    let width = points[5].0;
+    let hours = [0.0, 0.5, 4.0, 13.0, 30.0, 47.0];
+    assert_eq!(points[0].0, 0.0);
+    for (p, h) in points.iter().zip(hours.iter()) {
+        common::assert_close(p.0 / width, h / 47.0);
+    }
+
+    // Ticks sit on calendar boundaries, midnight shows the date
+    common::assert_close(common::tick(&svg, "x-line", "Dec 31").0, width * 24.0 / 47.0);
+    common::assert_close(common::tick(&svg, "x-line", "12:00").0, width * 12.0 / 47.0);
+    let ticks = common::attrs(&svg, "<g class=\"x-line\"", "transform");
+    assert_eq!(ticks.len(), 4);
+    for (k, t) in ticks.iter().enumerate() {
+        common::assert_close(common::translate(t).0, width * 12.0 * k as f32 / 47.0);
+    }
+    assert!(svg.contains(">Dec 30</text>"));
+}
+
+#[test]
+fn line_graph_area_to_file() {
+    let entries = vec![
+        Entry::new("Mon", 120),
+        Entry::new("Tue", 200),
+        Entry::new("Wed", -40),
+        Entry::new("Thu", 50),
+        Entry::new("Fri", 21),
+    ];
+    let line = LineBuilder::new()
+                .entries(entries)
+                .area(0.3)
+                .build();
+    match line.into_file("./images/line_area.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // The area follows the line and returns along the zero baseline
+    let svg = line.into_string();
+    let area = &common::attrs(&svg, "<path class=\"area\"", "d")[0];
+    let line = common::points(&common::attrs(&svg, "<path class=\"line\"", "d")[0]);
+    let points = common::points(area);
+    let zero = common::tick(&svg, "y-line", "0").1;
+    assert!(area.ends_with('Z'));
+    assert_eq!(&points[..5], &line[..]);
+    assert!(points[5..].iter().all(|p| p.1 == zero));
+    assert_eq!(points[9].0, line[0].0);
+    assert_eq!(common::attrs(&svg, "<path class=\"area\"", "fill-opacity")[0], "0.3");
+}
+
+fn traffic() -> Vec<Series> {
+    let desktop = vec![
+        Entry::new("Q1", 120),
+        Entry::new("Q2", 150),
+        Entry::new("Q3", 130),
+        Entry::new("Q4", 170),
+    ];
+    let mobile = vec![
+        Entry::new("Q1", 60),
+        Entry::new("Q2", 90),
+        Entry::new("Q3", 140),
+        Entry::new("Q4", 210),
+    ];
+    vec![Series::new("Desktop", desktop), Series::new("Mobile", mobile)]
+}
+
+#[test]
+fn line_graph_stacked_area_to_file() {
+    let line = traffic()
+                .into_iter()
+                .fold(LineBuilder::new(), |b, s| b.series(s))
+                .stacked()
+                .build();
+    match line.into_file("./images/line_stacked.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // The mobile area sits on the desktop line
+    let svg = line.into_string();
+    let areas = common::attrs(&svg, "<path class=\"area\"", "d");
+    let desktop = common::points(&common::attrs(&svg, "<path class=\"line\"", "d")[0]);
+    let mut bottom = common::points(&areas[1])[4..].to_vec();
+    bottom.reverse();
+    assert_eq!(bottom, desktop);
+}
+
+#[test]
+fn line_graph_normalized_area_to_file() {
+    let line = traffic()
+                .into_iter()
+                .fold(LineBuilder::new(), |b, s| b.series(s))
+                .normalized()
+                .build();
+    match line.into_file("./images/line_normalized.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // Every index adds up to 100%
+    let svg = line.into_string();
+    let top = common::tick(&svg, "y-line", "100").1;
+    let mobile = common::points(&common::attrs(&svg, "<path class=\"line\"", "d")[1]);
+    assert!(mobile.iter().all(|p| (p.1 - top).abs() < 0.01));
+}
+
+#[test]
+fn line_graph_interpolation_to_file() {
+    let modes = vec![
+        ("monotone", Interpolation::Monotone),
+        ("catmull_rom", Interpolation::CatmullRom),
+        ("step_before", Interpolation::StepBefore),
+        ("step_after", Interpolation::StepAfter),
+        ("step_middle", Interpolation::StepMiddle),
+    ];
+
+    for (name, interpolation) in modes.into_iter() {
+        let line = traffic()
+                    .into_iter()
+                    .fold(LineBuilder::new(), |b, s| b.series(s))
+                    .interpolation(interpolation)
+                    .stacked()
+                    .build();
+        match line.into_file(&format!("./images/line_{}.svg", name)) {
+            Err(e) => {
+                panic!("Couldn't save to file {}", e);
+            }
+            Ok(_) => {}
+        }
+    }
+}
+
+#[test]
+fn line_graph_markers_to_file() {
+    let line = traffic()
+                .into_iter()
+                .fold(LineBuilder::new(), |b, s| b.series(s))
+                .markers(Marker::Circle)
+                .marker_size(8.0)
+                .marker_fill("rgb(255,255,255)")
+                .marker_stroke("rgb(80,80,80)")
+                .data_labels()
+                .build();
+    match line.into_file("./images/line_markers.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    let svg = line.into_string();
+    let graph = common::without_legend(&svg);
+    assert_eq!(common::numbers(graph, "<circle class=\"marker\"", "r"), vec![4.0; 8]);
+    assert!(common::attrs(graph, "<circle class=\"marker\"", "fill").iter().all(|f| f == "rgb(255,255,255)"));
+    assert!(common::attrs(graph, "<circle class=\"marker\"", "stroke").iter().all(|f| f == "rgb(80,80,80)"));
+    assert_eq!(common::count(graph, "<text class=\"data-label\""), 8);
+    assert!(svg.contains(">210</text>"));
+
+    // The legend shows the marker on a line as wide as the series lines
+    let legend = common::legend(&svg);
+    assert_eq!(common::count(legend, "<circle class=\"marker\""), 2);
+    assert_eq!(common::attrs(legend, "<line class=\"swatch\"", "stroke-width"), vec!["2"; 2]);
+}
+
+#[test]
+fn line_graph_thinned_data_labels() {
+    // The labels of the second series would cover the first ones
+    let line = traffic()
+                .into_iter()
+                .chain(traffic().into_iter().take(1))
+                .fold(LineBuilder::new(), |b, s| b.series(s))
+                .markers(Marker::Circle)
+                .data_labels()
+                .build();
+    let svg = line.into_string();
+    assert_eq!(common::count(common::without_legend(&svg), "<circle class=\"marker\""), 12);
+    assert_eq!(common::count(&svg, "<text class=\"data-label\""), 8);
+}
+
+#[test]
+fn line_graph_gaps_to_file() {
+    let readings = |name, gaps| {
+        let entries = vec![
+            Entry::new("Mon", 12),
+            Entry::new("Tue", 15),
+            Entry::missing("Wed"),
+            Entry::new("Thu", 11),
+            Entry::new("Fri", 18),
+        ];
+        Series::new(name, entries).gaps(gaps)
+    };
+    let line = LineBuilder::new()
+                .series(readings("Break", Gaps::Break))
+                .series(readings("Connect", Gaps::Connect))
+                .series(readings("Zero", Gaps::Zero))
+                .markers(Marker::Square)
+                .build();
+    match line.into_file("./images/line_gaps.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    let svg = line.into_string();
+    let paths = common::attrs(&svg, "<path class=\"line\"", "d");
+    assert_eq!(common::subpaths(&paths[0]), 2);
+    assert_eq!(common::points(&paths[0]).len(), 4);
+    assert_eq!(common::subpaths(&paths[1]), 1);
+    assert_eq!(common::points(&paths[1]).len(), 4);
+    assert_eq!(common::subpaths(&paths[2]), 1);
+
+    let zero = common::points(&paths[2]);
+    assert_eq!(zero.len(), 5);
+    assert_eq!(zero[2].1, common::tick(&svg, "y-line", "0").1);
+    // Markers of the break and connect series skip the gap
+    assert_eq!(common::count(common::without_legend(&svg), "<rect class=\"marker\""), 4 + 4 + 5);
+    let legend = common::legend(&svg);
+    assert_eq!(common::count(legend, "<rect class=\"marker\""), 3);
+    assert_eq!(common::count(legend, "<circle"), 0);
+}
+
+#[test]
+fn line_graph_tick_options_to_file() {
+    let entries = vec![
+        Entry::new("Mon", 0.0012),
+        Entry::new("Tue", 0.0031),
+        Entry::new("Wed", 0.0048),
+        Entry::new("Thu", 0.0027),
+    ];
+    let line = LineBuilder::new()
+                .entries(entries)
+                .y_scale(ScaleOptions::new(ScaleKind::Linear).tick_count(4).nice(false))
+                .build();
+    match line.into_file("./images/line_ticks.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // Four ticks asked for give a step of 0.002, the domain is not
+    // extended so the largest value is at the top
+    let svg = line.into_string();
+    assert_eq!(common::tick_labels(&svg, "y-line"), vec!["0", "0.002", "0.004"]);
+    let paths = common::attrs(&svg, "<path class=\"line\"", "d");
+    assert_eq!(common::points(&paths[0])[2].1, 0.0);
+}
+
+#[test]
+fn line_graph_narrow_domain_to_file() {
+    let entries = vec![
+        Entry::new("Jan", 9620),
+        Entry::new("Feb", 9580),
+        Entry::new("Mar", 9710),
+        Entry::new("Apr", 9690),
+        Entry::new("May", 9760),
+    ];
+    let line = LineBuilder::new()
+                .entries(entries)
+                .area(0.3)
+                .y_scale(ScaleOptions::new(ScaleKind::Linear).include_zero(false).padding(0.1))
+                .build();
+    match line.into_file("./images/line_narrow.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // Without zero the ticks stay around the data
+    let svg = line.into_string();
+    let ticks = common::tick_labels(&svg, "y-line");
+    assert_eq!(ticks.first().unwrap(), "9560");
+    assert_eq!(ticks.last().unwrap(), "9780");
+    let paths = common::attrs(&svg, "<path class=\"line\"", "d");
+    let points = common::points(&paths[0]);
+    common::assert_close(points[1].1, common::tick(&svg, "y-line", "9580").1);
+    common::assert_close(points[4].1, common::tick(&svg, "y-line", "9760").1);
+}
+
+#[test]
+fn line_graph_reversed_to_file() {
+    let entries = vec![
+        Entry::new("Week 1", 7),
+        Entry::new("Week 2", 4),
+        Entry::new("Week 3", 5),
+        Entry::new("Week 4", 2),
+        Entry::new("Week 5", 1),
+    ];
+    let line = LineBuilder::new()
+                .entries(entries)
+                .markers(Marker::Circle)
+                .y_scale(ScaleOptions::new(ScaleKind::Linear).min(1.0).max(10.0).nice(false).reversed())
+                .build();
+    match line.into_file("./images/line_reversed.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // The explicit ends are kept exactly, with 1 at the top and 10 at the bottom
+    let svg = line.into_string();
+    let ticks = common::tick_labels(&svg, "y-line");
+    assert_eq!(ticks.first().unwrap(), "1");
+    assert_eq!(ticks.last().unwrap(), "10");
+    assert_eq!(common::tick(&svg, "y-line", "1").1, 0.0);
+    let bottom = common::tick(&svg, "y-line", "10").1;
+    assert!(bottom > 0.0);
+    let paths = common::attrs(&svg, "<path class=\"line\"", "d");
+    let points = common::points(&paths[0]);
+    common::assert_close(points[0].1, common::tick(&svg, "y-line", "7").1);
+    assert_eq!(points[4].1, 0.0);
+    assert!(points[0].1 > points[3].1);
+}
+
+#[test]
+fn line_graph_many_labels_to_file() {
+    let entries = (1..51)
+        .map(|d| Entry::new(format!("2024-03-{:02}", (d - 1) % 31 + 1), (d * 37 % 50) as f32))
+        .collect();
+    let line = LineBuilder::new()
+                .entries(entries)
+                .build();
+    match line.into_file("./images/line_many_labels.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // Even vertical labels would overlap, so every other one is
+    // skipped while all of them keep their grid line
+    let svg = line.into_string();
+    assert_eq!(common::count(&svg, "<g class=\"x-line\""), 50);
+    let labels = common::tick_labels(&svg, "x-line");
+    assert_eq!(labels.len(), 25);
+    assert_eq!(&labels[..3], &["2024-03-01", "2024-03-03", "2024-03-05"]);
+    let rotations = common::attrs(&svg, "<text class=\"label\" transform=\"rotate(", "transform");
+    assert!(!rotations.is_empty());
+    assert!(rotations.iter().all(|r| r.starts_with("rotate(-90,")));
+}
+
+#[test]
+fn line_graph_secondary_axis_to_file() {
+    let revenue = vec![
+        Entry::new("Jan", 48000),
+        Entry::new("Feb", 52000),
+        Entry::new("Mar", 61000),
+        Entry::new("Apr", 58000),
+        Entry::new("May", 67000),
+    ];
+    let conversion = vec![
+        Entry::new("Jan", 2.1),
+        Entry::new("Feb", 2.4),
+        Entry::new("Mar", 3.2),
+        Entry::new("Apr", 2.9),
+        Entry::new("May", 3.5),
+    ];
+    let line = LineBuilder::new()
+                .series(Series::new("Revenue", revenue))
+                .series(Series::new("Conversion rate", conversion).y_axis(YAxis::Right))
+                .y_title("Revenue")
+                .y2_title("Conversion")
+                .build();
+    match line.into_file("./images/line_secondary_axis.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    let svg = line.into_string();
+    assert_eq!(common::count(&svg, "<g class=\"y2-axis\""), 1);
+    assert!(svg.contains(">3.5</text>"));
+    assert!(svg.contains(">Conversion</text>"));
+
+    // The right axis runs from 0 to 3.5, so the last conversion rate
+    // is at the top, while on the revenue scale it would be at the bottom
+    let paths = common::attrs(&svg, "<path class=\"line\"", "d");
+    let revenue = common::points(&paths[0]);
+    let conversion = common::points(&paths[1]);
+    assert_eq!(conversion[4].1, 0.0);
+    assert!(revenue[4].1 > 0.0);
+    let height = common::attrs(&svg[svg.find("y2-axis").unwrap()..], "<line", "y2")[0]
+        .parse::<f32>()
+        .unwrap();
+    assert!((conversion[0].1 - height * (1.0 - 2.1 / 3.5)).abs() < 0.01);
+}
+
+#[test]
+fn line_graph_no_values() {
+    let entries = vec![
+        Entry::missing("Mon"),
+        Entry::missing("Tue"),
+    ];
+    let line = LineBuilder::new()
+                .entries(entries)
+                .y_scale(ScaleOptions::new(ScaleKind::Linear).include_zero(false))
+                .build();
+    assert!(!line.into_string().contains("NaN"));
+
+    let line = LineBuilder::new()
+                .entries(vec![])
+                .y_scale(ScaleKind::Log(10.0))
+                .build();
+    assert!(!line.into_string().contains("NaN"));
+}
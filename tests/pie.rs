@@ -1,6 +1,8 @@
 extern crate svg_graph;
 
-use svg_graph::{Graph, PieBuilder, Entry};
+mod common;
+
+use svg_graph::{Graph, PieBuilder, Pie, Entry, Theme, Direction, PieLabels, SliceText};
 
 #[test]
 fn pie_graph_to_file() {
@@ -24,3 +26,217 @@ fn pie_graph_to_file() {
         Ok(_) => {}
     }
 }
+
+#[test]
+fn pie_graph_dark_theme_to_file() {
+    let entries = vec![
+        Entry::new("Linux", 45),
+        Entry::new("macOS", 30),
+        Entry::new("Windows", 20),
+        Entry::new("Other", 5),
+    ];
+    let pie = PieBuilder::new()
+                .theme(Theme::dark())
+                .title("Operating systems")
+                .entries(entries)
+                .build();
+    match pie.into_file("./images/pie_dark.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+}
+
+#[test]
+fn donut_graph_to_file() {
+    let entries = vec![
+        Entry::new("Rent", 1200),
+        Entry::new("Food", 450),
+        Entry::new("Transport", 180),
+        Entry::new("Savings", 600),
+    ];
+    let pie = PieBuilder::new()
+                .entries(entries)
+                .inner_radius(0.6)
+                .center_text("2430")
+                .start_angle(0.0)
+                .direction(Direction::Clockwise)
+                .pad_angle(2.0)
+                .corner_radius(6.0)
+                .build();
+    match pie.into_file("./images/donut.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // Every slice has an outer and an inner arc, each drawn in two
+    // halves, and four rounded corners
+    let svg = pie.into_string();
+    let paths = common::attrs(&svg, "<path stroke", "d");
+    assert_eq!(paths.len(), 4);
+    for d in paths.iter() {
+        let arcs = common::arcs(d);
+        assert_eq!(arcs.len(), 4);
+        common::assert_close(arcs[2].0 / arcs[0].0, 0.6);
+        assert_eq!((arcs[0].1, arcs[2].1), (1, 0));
+        assert_eq!(d.matches('Q').count(), 4);
+    }
+    assert!(svg.contains(">2430</text>"));
+}
+
+#[test]
+fn pie_graph_start_angle_direction() {
+    let entries = || vec![
+        Entry::new("a", 1),
+        Entry::new("b", 3),
+    ];
+    let slices = |pie: Pie| common::attrs(&pie.into_string(), "<path stroke", "d");
+
+    // A quarter from 12 to 3 o'clock
+    let paths = slices(PieBuilder::new()
+                           .entries(entries())
+                           .start_angle(0.0)
+                           .direction(Direction::Clockwise)
+                           .build());
+    let (x, y) = common::start(&paths[0]);
+    let arcs = common::arcs(&paths[0]);
+    let r = arcs[0].0;
+    common::assert_close(x, 0.0);
+    common::assert_close(y, -r);
+    assert!(arcs.iter().all(|a| a.1 == 1));
+    common::assert_close(arcs[1].2, r);
+    common::assert_close(arcs[1].3, 0.0);
+
+    // Half of the pad angle on each side of a slice
+    let paths = slices(PieBuilder::new()
+                           .entries(entries())
+                           .start_angle(0.0)
+                           .direction(Direction::Clockwise)
+                           .pad_angle(10.0)
+                           .build());
+    let (x, y) = common::start(&paths[0]);
+    common::assert_close(x, r * 5.0f32.to_radians().sin());
+    common::assert_close(y, -r * 5.0f32.to_radians().cos());
+    let end = common::arcs(&paths[0])[1];
+    common::assert_close(end.2, r * 85.0f32.to_radians().sin());
+
+    // By default from 9 to 6 o'clock
+    let paths = slices(PieBuilder::new().entries(entries()).build());
+    let (x, y) = common::start(&paths[0]);
+    let arcs = common::arcs(&paths[0]);
+    common::assert_close(x, -r);
+    common::assert_close(y, 0.0);
+    assert!(arcs.iter().all(|a| a.1 == 0));
+    common::assert_close(arcs[1].2, 0.0);
+    common::assert_close(arcs[1].3, r);
+}
+
+#[test]
+fn pie_graph_outside_labels_to_file() {
+    let entries = vec![
+        Entry::new("Chrome", 640),
+        Entry::new("Safari", 190),
+        Entry::new("Edge", 52),
+        Entry::new("Firefox", 31),
+        Entry::new("Opera", 22),
+        Entry::new("Samsung Internet", 21),
+        Entry::new("UC Browser", 9),
+        Entry::new("Other", 5),
+    ];
+    let pie = PieBuilder::new()
+                .entries(entries)
+                .slice_labels(PieLabels::Outside)
+                .label_threshold(0.01)
+                .build();
+    match pie.into_file("./images/pie_outside.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // Six slices are above 1%, the last two are only in the legend
+    let svg = pie.into_string();
+    let slices = &svg[svg.find("<g class=\"content\"").unwrap()..];
+    assert_eq!(common::count(slices, "<polyline class=\"leader\""), 6);
+    assert_eq!(common::count(slices, "<text"), 6);
+    assert!(slices.contains(">Opera (22)</text>"));
+    assert!(!slices.contains("UC Browser"));
+    assert!(!slices.contains("Other"));
+
+    // Labels on the same side are at least a line apart
+    for anchor in ["start", "end"].iter() {
+        let mut ys = common::numbers(slices, "<text", "y")
+            .into_iter()
+            .zip(common::attrs(slices, "<text", "text-anchor").into_iter())
+            .filter(|&(_, ref a)| a == anchor)
+            .map(|(y, _)| y)
+            .collect::<Vec<f32>>();
+        ys.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!(ys.windows(2).all(|w| w[1] - w[0] >= 12.0), "{:?}", ys);
+    }
+}
+
+#[test]
+fn pie_graph_other_slice_to_file() {
+    let entries = (1..36)
+        .map(|i| Entry::new(format!("Region {}", i), 1000 / i))
+        .collect();
+    let pie = PieBuilder::new()
+                .entries(entries)
+                .top(8)
+                .other_label("Remaining regions")
+                .build();
+    match pie.into_file("./images/pie_other.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // The eight largest regions and one slice for the other 27
+    let svg = pie.into_string();
+    let other = (9..36).fold(0, |acc, i| acc + 1000 / i);
+    assert_eq!(common::count(&svg, "<path stroke"), 9);
+    assert!(svg.contains(&format!(">Remaining regions ({})</text>", other)[..]));
+    assert!(svg.contains(">Region 8 (125)</text>"));
+    assert!(!svg.contains("Region 9"));
+
+    // Regions below 5% of the total are merged as well
+    let entries = (1..36)
+        .map(|i| Entry::new(format!("Region {}", i), 1000 / i))
+        .collect();
+    let svg = PieBuilder::new()
+                .entries(entries)
+                .min_fraction(0.05)
+                .build()
+                .into_string();
+    assert_eq!(common::count(&svg, "<path stroke"), 5);
+    assert!(svg.contains(">Region 4 (250)</text>"));
+    assert!(!svg.contains("Region 5"));
+    assert!(svg.contains(">Other ("));
+}
+
+#[test]
+fn pie_graph_percent_to_file() {
+    let entries = vec![
+        Entry::new("Linux", 45),
+        Entry::new("macOS", 30),
+        Entry::new("Windows", 20),
+        Entry::new("Other", 7),
+    ];
+    let pie = PieBuilder::new()
+                .entries(entries)
+                .slice_text(SliceText::Percent)
+                .decimals(1)
+                .build();
+    match pie.into_file("./images/pie_percent.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+}
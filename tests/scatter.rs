@@ -0,0 +1,142 @@
+extern crate svg_graph;
+
+mod common;
+
+use svg_graph::{Graph, ScatterBuilder, Point, Marker};
+
+#[test]
+fn scatter_graph_to_file() {
+    let points = vec![
+        Point::new(1.2, 34),
+        Point::new(2.8, 41),
+        Point::new(3.1, 38.5),
+        Point::new(4.6, 52),
+        Point::new(5.0, 49).size(14.0),
+        Point::new(6.3, 61).color("rgb(1,120,111)"),
+        Point::new(7.7, 58),
+    ];
+    let scatter = ScatterBuilder::new()
+                .points(points)
+                .x_title("Hours")
+                .y_title("Score")
+                .build();
+    match scatter.into_file("./images/scatter.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // Points without their own size and color use the graph defaults
+    let svg = scatter.into_string();
+    let radii = common::numbers(&svg, "<circle class=\"marker\"", "r");
+    let fills = common::attrs(&svg, "<circle class=\"marker\"", "fill");
+    assert_eq!(radii, vec![4.0, 4.0, 4.0, 4.0, 7.0, 4.0, 4.0]);
+    assert_eq!(fills[5], "rgb(1,120,111)");
+    assert!(fills.iter().enumerate().all(|(i, f)| i == 5 || f == "rgb(237,10,63)"));
+
+    // Both axes have numeric ticks around the data, not category labels
+    for class in ["x-line", "y-line"].iter() {
+        let ticks = common::tick_labels(&svg, class)
+            .iter()
+            .map(|t| t.parse::<f32>().expect("numeric tick"))
+            .collect::<Vec<f32>>();
+        assert!(ticks.len() > 2);
+        assert!(ticks.windows(2).all(|w| (w[1] - w[0]) * (ticks[1] - ticks[0]) > 0.0));
+    }
+    let x = common::tick_labels(&svg, "x-line");
+    assert!(x.first().unwrap().parse::<f32>().unwrap() <= 1.2);
+    assert!(x.last().unwrap().parse::<f32>().unwrap() >= 7.7);
+    assert!(svg.contains(">Hours</text>"));
+}
+
+#[test]
+fn scatter_graph_markers_to_file() {
+    let markers = vec![Marker::Square, Marker::Triangle, Marker::Cross];
+
+    for (i, marker) in markers.into_iter().enumerate() {
+        let points = (0..20)
+            .map(|x| Point::new(x - 10, (x * x) as f32 / 10.0 - 5.0))
+            .collect();
+        let scatter = ScatterBuilder::new()
+                    .points(points)
+                    .marker(marker)
+                    .marker_size(10.0)
+                    .build();
+        match scatter.into_file(&format!("./images/scatter_marker_{}.svg", i)) {
+            Err(e) => {
+                panic!("Couldn't save to file {}", e);
+            }
+            Ok(_) => {}
+        }
+
+        // Every point is drawn with the element of its shape
+        let svg = scatter.into_string();
+        let element = ["<rect class=\"marker\"", "<polygon class=\"marker\"", "<path class=\"marker\""][i];
+        assert_eq!(common::count(&svg, element), 20);
+        assert_eq!(common::count(&svg, "class=\"marker\""), 20);
+    }
+}
+
+#[test]
+fn bubble_graph_to_file() {
+    // Life expectancy by income with population as the bubble size
+    let points = vec![
+        Point::new(1.5, 62).value(210),
+        Point::new(4.2, 68).value(1380),
+        Point::new(9.8, 72).value(1410),
+        Point::new(12.0, 76).value(84),
+        Point::new(38.0, 81).value(67),
+        Point::new(46.0, 82).value(83),
+        Point::new(63.0, 79).value(331),
+    ];
+    let scatter = ScatterBuilder::new()
+                .points(points)
+                .bubbles(60.0)
+                .x_title("Income")
+                .y_title("Life expectancy")
+                .build();
+    match scatter.into_file("./images/bubble.svg") {
+        Err(e) => {
+            panic!("Couldn't save to file {}", e);
+        }
+        Ok(_) => {}
+    }
+
+    // Largest bubbles first so that the small ones stay on top
+    let svg = scatter.into_string();
+    let radii = common::numbers(&svg, "<circle class=\"marker\"", "r");
+    assert_eq!(radii.len(), 7);
+    assert_eq!(radii[0], 30.0);
+    assert!(radii.windows(2).all(|w| w[0] >= w[1]), "{:?}", radii);
+
+    // Reference bubbles at nice values up to the largest one
+    let legend = &svg[svg.find("<g class=\"size-legend\"").expect("no size legend")..];
+    assert_eq!(common::count(legend, "<circle"), 3);
+    for label in ["100", "500", "1000"].iter() {
+        assert!(legend.contains(&format!(">{}</text>", label)[..]));
+    }
+}
+
+#[test]
+fn bubble_graph_sizes() {
+    let points = vec![
+        Point::new(1, 1).value(100),
+        Point::new(2, 2).value(400),
+    ];
+    let svg = ScatterBuilder::new()
+                .points(points)
+                .bubbles(60.0)
+                .build()
+                .into_string();
+
+    // Four times the value gives four times the area, twice the radius
+    let radii = common::numbers(&svg, "<circle class=\"marker\"", "r");
+    assert_eq!(radii, vec![30.0, 15.0]);
+
+    let legend = &svg[svg.find("<g class=\"size-legend\"").unwrap()..];
+    let legend_radii = common::numbers(legend, "<circle", "r");
+    assert_eq!(legend_radii.len(), 2);
+    common::assert_close(legend_radii[0], 30.0 * (20.0f32 / 400.0).sqrt());
+    common::assert_close(legend_radii[1], 30.0 * (200.0f32 / 400.0).sqrt());
+}